@@ -0,0 +1,678 @@
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
+
+use utilities::{
+    InputSource, Part, RunOptions, bench::BenchConfig, fetch, inputs::InputSettings,
+    report::ReportFormat,
+};
+
+pub const USAGE: &str = "\
+Usage: advent_of_code_2025 [DAYS] [--part 1|2|both] [--input FILE | --input-path PATH|-]
+                           [--profiles] [--jobs N] [--report json|csv] [--report-file PATH]
+       advent_of_code_2025 bench [DAYS] [--part 1|2|both] [--input FILE] [--warmup N]
+                           [--samples N] [--budget-ms N] [--baseline PATH] [--save-baseline PATH]
+       advent_of_code_2025 fetch [DAYS] [--cache-dir PATH] [--refresh]
+       advent_of_code_2025 submit DAY --part 1|2 [--answer TEXT] [--input FILE] [--cache-dir PATH]
+       advent_of_code_2025 generate DAY [--size N] [--seed N]
+       advent_of_code_2025 new DAY
+       advent_of_code_2025 start DAY
+       advent_of_code_2025 solve DAY --part 1|2 [--input FILE] [--note TEXT]
+       advent_of_code_2025 notes
+
+  DAYS              all (default), a day (3), a range (1-5) or a list (1,4,7-9)
+  --part, -p        which part to run (default: both)
+  --input, -i       input file name inside dayN/ under the input root (default: input.txt)
+  --input-path      read a single day's input from any file, or from stdin for '-'; answers
+                    aren't checked against answers.toml
+  --input-root      directory holding the dayN/ directories (default: AOC_INPUT_ROOT, input_root
+                    in aoc.toml, or test_files/ next to Cargo.toml)
+  --input-set       use the input root aoc.toml lists under this name in [input_sets]
+                    (default: AOC_INPUT_SET or input_set in aoc.toml)
+  --profiles        run against everyone's input, dayN/input.txt and dayN/inputs/<name>.txt,
+                    and show the results as a table
+  --jobs, -j        run days and parts on this many threads (default: 1)
+  --report          also write a machine-readable record per part, as JSON lines or CSV
+  --report-file     where to write the report (default: run_report.jsonl or run_report.csv)
+
+bench:
+  --warmup          untimed runs before sampling (default: 3)
+  --samples         timed runs per part (default: 25)
+  --budget-ms       stop sampling a part after this long (default: 5000)
+  --baseline        compare medians against a saved baseline
+  --save-baseline   save the medians of this run as a baseline
+
+fetch:              download missing dayN/input.txt files, using the AOC_SESSION cookie
+  --cache-dir       where downloaded inputs are kept (default: .aoc-cache)
+  --refresh         ask the site again even if the input is already there
+
+submit:             send an answer to the site, never repeating one already ruled out
+  --answer          the answer to send (default: run the part on --input)
+  --cache-dir       where submissions.toml, the log of sent answers, is kept (default: .aoc-cache)
+
+generate:           print a random input for DAY, e.g. to pipe into '--input-path -' or bench
+  --size            roughly how many lines, ranges or devices to generate (default: 100)
+  --seed            makes the input reproducible (default: from the clock, printed to stderr)
+
+days and journal:
+  new               create src/dayN.rs from src/template_day.rs, register it in main.rs and add
+                    dayN/ under the input root and a journal.toml entry
+  start             record in journal.toml that DAY was started now
+  solve             run a part and record its answer, runtime and solve time in journal.toml
+  --note            a remark to keep alongside the solved part
+  notes             regenerate notes.txt from journal.toml";
+
+/// Advent of Code 2025 has twelve puzzles, so days can be started before they have a solver.
+pub const LAST_DAY: usize = 12;
+
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    Run,
+    Bench(BenchArgs),
+    Fetch(FetchArgs),
+    Submit(SubmitArgs),
+    Generate(GenerateArgs),
+    New,
+    Start,
+    Solve { note: Option<String> },
+    Notes,
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct BenchArgs {
+    pub config: BenchConfig,
+    pub baseline: Option<PathBuf>,
+    pub save_baseline: Option<PathBuf>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct FetchArgs {
+    pub cache_dir: PathBuf,
+    pub refresh: bool,
+}
+
+impl Default for FetchArgs {
+    fn default() -> Self {
+        Self {
+            cache_dir: PathBuf::from(fetch::DEFAULT_CACHE_DIR),
+            refresh: false,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct SubmitArgs {
+    pub answer: Option<String>,
+    pub cache_dir: PathBuf,
+}
+
+impl Default for SubmitArgs {
+    fn default() -> Self {
+        Self {
+            answer: None,
+            cache_dir: PathBuf::from(fetch::DEFAULT_CACHE_DIR),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct GenerateArgs {
+    pub size: usize,
+    pub seed: Option<u64>,
+}
+
+impl Default for GenerateArgs {
+    fn default() -> Self {
+        Self {
+            size: 100,
+            seed: None,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Args {
+    pub command: Command,
+    pub days: Vec<usize>,
+    pub options: RunOptions,
+    pub report: Option<(ReportFormat, PathBuf)>,
+    pub jobs: usize,
+    pub inputs: InputSettings,
+    pub profiles: bool,
+}
+
+/// Returns `Ok(None)` when help was requested.
+pub fn parse_args<I>(args: I, available_days: &[usize]) -> Result<Option<Args>, String>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut days = None;
+    let mut options = RunOptions::default();
+    let mut report_format = None;
+    let mut report_file = None;
+    let mut jobs = 1;
+    let mut inputs = InputSettings::default();
+    let mut input_given = false;
+    let mut profiles = false;
+
+    let mut args = args.into_iter().peekable();
+
+    let mut command = match args.peek().map(String::as_str) {
+        Some("bench") => Command::Bench(BenchArgs::default()),
+        Some("fetch") => Command::Fetch(FetchArgs::default()),
+        Some("submit") => Command::Submit(SubmitArgs::default()),
+        Some("generate") => Command::Generate(GenerateArgs::default()),
+        Some("new") => Command::New,
+        Some("start") => Command::Start,
+        Some("solve") => Command::Solve { note: None },
+        Some("notes") => Command::Notes,
+        _ => Command::Run,
+    };
+
+    if command != Command::Run {
+        args.next();
+    }
+
+    let all_days = (1..=LAST_DAY).collect::<Vec<_>>();
+    let day_choices = match command {
+        Command::Fetch(_) | Command::Submit(_) | Command::New | Command::Start => &all_days,
+        _ => available_days,
+    };
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--warmup" => {
+                bench_args(&mut command, &arg)?.config.warmup = next_number(&mut args, &arg)?
+            }
+            "--samples" => {
+                let samples = next_number(&mut args, &arg)?;
+                if samples == 0 {
+                    return Err("'--samples' must be at least 1".to_string());
+                }
+
+                bench_args(&mut command, &arg)?.config.samples = samples;
+            }
+            "--budget-ms" => {
+                let budget = Duration::from_millis(next_number(&mut args, &arg)?);
+
+                bench_args(&mut command, &arg)?.config.budget = budget;
+            }
+            "--baseline" => {
+                let path = PathBuf::from(next_value(&mut args, &arg)?);
+
+                bench_args(&mut command, &arg)?.baseline = Some(path);
+            }
+            "--save-baseline" => {
+                let path = PathBuf::from(next_value(&mut args, &arg)?);
+
+                bench_args(&mut command, &arg)?.save_baseline = Some(path);
+            }
+            "--cache-dir" => {
+                let path = PathBuf::from(next_value(&mut args, &arg)?);
+
+                match &mut command {
+                    Command::Fetch(FetchArgs { cache_dir, .. })
+                    | Command::Submit(SubmitArgs { cache_dir, .. }) => *cache_dir = path,
+                    _ => return Err(format!("'{arg}' is only supported by fetch and submit")),
+                }
+            }
+            "--refresh" => fetch_args(&mut command, &arg)?.refresh = true,
+            "--answer" => {
+                let answer = next_value(&mut args, &arg)?;
+
+                match &mut command {
+                    Command::Submit(submit_args) => submit_args.answer = Some(answer),
+                    _ => return Err(format!("'{arg}' is only supported by submit")),
+                }
+            }
+            "--size" => {
+                let size = next_number(&mut args, &arg)?;
+                if size == 0 {
+                    return Err("'--size' must be at least 1".to_string());
+                }
+
+                generate_args(&mut command, &arg)?.size = size;
+            }
+            "--seed" => {
+                let seed = next_number(&mut args, &arg)?;
+
+                generate_args(&mut command, &arg)?.seed = Some(seed);
+            }
+            "--note" => {
+                let text = next_value(&mut args, &arg)?;
+
+                match &mut command {
+                    Command::Solve { note } => *note = Some(text),
+                    _ => return Err(format!("'{arg}' is only supported by solve")),
+                }
+            }
+            "--part" | "-p" => {
+                options.part = next_value(&mut args, &arg)?.parse::<Part>()?;
+            }
+            "--input" | "-i" | "--input-path" => {
+                if input_given {
+                    return Err("'--input' and '--input-path' can only be given once".to_string());
+                }
+
+                let value = next_value(&mut args, &arg)?;
+                options.input = match arg.as_str() {
+                    "--input-path" => value.parse()?,
+                    _ => InputSource::Data(value),
+                };
+                input_given = true;
+            }
+            "--profiles" => profiles = true,
+            "--input-root" => {
+                inputs.root = Some(PathBuf::from(next_value(&mut args, &arg)?));
+            }
+            "--input-set" => {
+                inputs.set = Some(next_value(&mut args, &arg)?);
+            }
+            "--jobs" | "-j" => {
+                jobs = next_number(&mut args, &arg)?;
+                if jobs == 0 {
+                    return Err(format!("'{arg}' must be at least 1"));
+                }
+            }
+            "--report" => {
+                report_format = Some(next_value(&mut args, &arg)?.parse::<ReportFormat>()?);
+            }
+            "--report-file" => {
+                report_file = Some(PathBuf::from(next_value(&mut args, &arg)?));
+            }
+            "--help" | "-h" => return Ok(None),
+            flag if flag.starts_with('-') => return Err(format!("unknown option '{flag}'")),
+            _ if days.is_some() => return Err(format!("unexpected argument '{arg}'")),
+            _ => days = Some(parse_days(&arg, day_choices)?),
+        }
+    }
+
+    match &command {
+        Command::New | Command::Start | Command::Solve { .. } | Command::Submit(_)
+            if days.as_ref().is_none_or(|days| days.len() != 1) =>
+        {
+            return Err("'new', 'start', 'solve' and 'submit' need a single day".to_string());
+        }
+        Command::Generate(_) if days.as_ref().is_none_or(|days| days.len() != 1) => {
+            return Err("'generate' needs a single day".to_string());
+        }
+        Command::Solve { .. } | Command::Submit(_) if options.part == Part::Both => {
+            return Err("'solve' and 'submit' need '--part 1' or '--part 2'".to_string());
+        }
+        _ if !matches!(options.input, InputSource::Data(_))
+            && days.as_ref().is_none_or(|days| days.len() != 1) =>
+        {
+            return Err("'--input-path' needs a single day".to_string());
+        }
+        Command::Notes if days.is_some() => {
+            return Err("'notes' doesn't take days".to_string());
+        }
+        _ => {}
+    }
+
+    let days = days.unwrap_or_else(|| day_choices.to_vec());
+
+    let report = match (report_format, report_file) {
+        (Some(format), file) => Some((
+            format,
+            file.unwrap_or_else(|| format.default_file_name().into()),
+        )),
+        (None, Some(_)) => return Err("'--report-file' requires '--report'".to_string()),
+        (None, None) => None,
+    };
+
+    if report.is_some() && command != Command::Run {
+        return Err("'--report' is only supported when running puzzles".to_string());
+    }
+
+    if profiles {
+        if command != Command::Run {
+            return Err("'--profiles' is only supported when running puzzles".to_string());
+        }
+        if input_given || report.is_some() {
+            return Err("'--profiles' can't be combined with '--input' or '--report'".to_string());
+        }
+    }
+
+    if jobs > 1 && command != Command::Run {
+        return Err("'--jobs' is only supported when running puzzles".to_string());
+    }
+
+    Ok(Some(Args {
+        command,
+        days,
+        options,
+        report,
+        jobs,
+        inputs,
+        profiles,
+    }))
+}
+
+fn bench_args<'a>(command: &'a mut Command, flag: &str) -> Result<&'a mut BenchArgs, String> {
+    match command {
+        Command::Bench(bench_args) => Ok(bench_args),
+        _ => Err(format!("'{flag}' is only supported by bench")),
+    }
+}
+
+fn fetch_args<'a>(command: &'a mut Command, flag: &str) -> Result<&'a mut FetchArgs, String> {
+    match command {
+        Command::Fetch(fetch_args) => Ok(fetch_args),
+        _ => Err(format!("'{flag}' is only supported by fetch")),
+    }
+}
+
+fn generate_args<'a>(command: &'a mut Command, flag: &str) -> Result<&'a mut GenerateArgs, String> {
+    match command {
+        Command::Generate(generate_args) => Ok(generate_args),
+        _ => Err(format!("'{flag}' is only supported by generate")),
+    }
+}
+
+fn next_number<T: FromStr>(
+    args: &mut impl Iterator<Item = String>,
+    flag: &str,
+) -> Result<T, String> {
+    let value = next_value(args, flag)?;
+
+    value
+        .parse()
+        .map_err(|_| format!("invalid value '{value}' for '{flag}'"))
+}
+
+fn next_value(args: &mut impl Iterator<Item = String>, flag: &str) -> Result<String, String> {
+    args.next()
+        .ok_or_else(|| format!("missing value for '{flag}'"))
+}
+
+fn parse_days(text: &str, available_days: &[usize]) -> Result<Vec<usize>, String> {
+    if text == "all" {
+        return Ok(available_days.to_vec());
+    }
+
+    let mut days = vec![];
+
+    for piece in text.split(",") {
+        let (start, end) = match piece.split_once("-") {
+            Some((start, end)) => (
+                parse_day(start, available_days)?,
+                parse_day(end, available_days)?,
+            ),
+            None => {
+                let day = parse_day(piece, available_days)?;
+
+                (day, day)
+            }
+        };
+
+        if start > end {
+            return Err(format!("invalid day range '{piece}'"));
+        }
+
+        days.extend((start..=end).filter(|day| available_days.contains(day)));
+    }
+
+    days.sort();
+    days.dedup();
+
+    Ok(days)
+}
+
+fn parse_day(text: &str, available_days: &[usize]) -> Result<usize, String> {
+    let day = text
+        .trim()
+        .parse::<usize>()
+        .map_err(|_| format!("invalid day '{text}'"))?;
+
+    if !available_days.contains(&day) {
+        return Err(format!("day {day} is not available"));
+    }
+
+    Ok(day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAYS: [usize; 11] = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11];
+
+    fn parse(args: &[&str]) -> Result<Args, String> {
+        parse_args(args.iter().map(|s| s.to_string()), &DAYS).map(Option::unwrap)
+    }
+
+    #[test]
+    fn test_defaults() {
+        let args = parse(&[]).unwrap();
+
+        assert_eq!(args.days, (1..=11).collect::<Vec<_>>());
+        assert_eq!(args.options.part, Part::Both);
+        assert_eq!(
+            args.options.input,
+            InputSource::Data("input.txt".to_string())
+        );
+        assert_eq!(args.jobs, 1);
+        assert_eq!(args.inputs, InputSettings::default());
+        assert!(!args.profiles);
+    }
+
+    #[test]
+    fn test_day_list_and_ranges() {
+        let args = parse(&["7-9,1,8", "--part", "2", "-i", "example.txt", "-j", "4"]).unwrap();
+
+        assert_eq!(args.days, vec![1, 7, 8, 9]);
+        assert_eq!(args.jobs, 4);
+        assert_eq!(args.options.part, Part::Two);
+        assert_eq!(
+            args.options.input,
+            InputSource::Data("example.txt".to_string())
+        );
+    }
+
+    #[test]
+    fn test_invalid_args() {
+        assert!(parse(&["12"]).is_err());
+        assert!(parse(&["5-3"]).is_err());
+        assert!(parse(&["--part", "3"]).is_err());
+        assert!(parse(&["--input"]).is_err());
+        assert!(parse(&["1", "2"]).is_err());
+        assert!(parse(&["--report", "xml"]).is_err());
+        assert!(parse(&["--report-file", "out.csv"]).is_err());
+        assert!(parse(&["--samples", "5"]).is_err());
+        assert!(parse(&["bench", "--samples", "0"]).is_err());
+        assert!(parse(&["bench", "--report", "csv"]).is_err());
+        assert!(parse(&["--jobs", "0"]).is_err());
+        assert!(parse(&["bench", "-j", "4"]).is_err());
+        assert!(parse(&["--note", "hi"]).is_err());
+        assert!(parse(&["start"]).is_err());
+        assert!(parse(&["start", "13"]).is_err());
+        assert!(parse(&["solve", "3-4", "--part", "1"]).is_err());
+        assert!(parse(&["solve", "3"]).is_err());
+        assert!(parse(&["notes", "3"]).is_err());
+    }
+
+    #[test]
+    fn test_input_root_and_set() {
+        let args = parse(&["3", "--input-root", "/data/aoc", "--input-set", "alice"]).unwrap();
+
+        assert_eq!(
+            args.inputs,
+            InputSettings {
+                root: Some(PathBuf::from("/data/aoc")),
+                set: Some("alice".to_string()),
+            }
+        );
+        assert!(parse(&["--input-set"]).is_err());
+    }
+
+    #[test]
+    fn test_input_path() {
+        let args = parse(&["5", "-p", "1", "--input-path", "-"]).unwrap();
+        assert_eq!(args.options.input, InputSource::Stdin);
+
+        let args = parse(&["bench", "5", "--input-path", "/tmp/big.txt"]).unwrap();
+        assert_eq!(
+            args.options.input,
+            InputSource::Path(PathBuf::from("/tmp/big.txt"))
+        );
+
+        assert!(parse(&["--input-path", "-"]).is_err());
+        assert!(parse(&["1-2", "--input-path", "-"]).is_err());
+        assert!(parse(&["5", "--input-path", ""]).is_err());
+        assert!(parse(&["5", "--input-path", "-", "-i", "example.txt"]).is_err());
+    }
+
+    #[test]
+    fn test_generate() {
+        let args = parse(&["generate", "10", "--size", "500", "--seed", "7"]).unwrap();
+        assert_eq!(
+            args.command,
+            Command::Generate(GenerateArgs {
+                size: 500,
+                seed: Some(7),
+            })
+        );
+        assert_eq!(args.days, vec![10]);
+
+        let args = parse(&["generate", "3"]).unwrap();
+        assert_eq!(args.command, Command::Generate(GenerateArgs::default()));
+
+        assert!(parse(&["generate"]).is_err());
+        assert!(parse(&["generate", "1-2"]).is_err());
+        assert!(parse(&["generate", "3", "--size", "0"]).is_err());
+        assert!(parse(&["3", "--seed", "7"]).is_err());
+    }
+
+    #[test]
+    fn test_profiles() {
+        let args = parse(&["1-3", "--profiles", "-j", "4"]).unwrap();
+
+        assert!(args.profiles);
+        assert!(parse(&["--profiles", "-i", "example.txt"]).is_err());
+        assert!(parse(&["--profiles", "--report", "csv"]).is_err());
+        assert!(parse(&["bench", "--profiles"]).is_err());
+    }
+
+    #[test]
+    fn test_report() {
+        let args = parse(&["--report", "csv"]).unwrap();
+        assert_eq!(
+            args.report,
+            Some((ReportFormat::Csv, PathBuf::from("run_report.csv")))
+        );
+
+        let args = parse(&["--report", "json", "--report-file", "out.jsonl"]).unwrap();
+        assert_eq!(
+            args.report,
+            Some((ReportFormat::JsonLines, PathBuf::from("out.jsonl")))
+        );
+    }
+
+    #[test]
+    fn test_bench() {
+        let args = parse(&[
+            "bench",
+            "8",
+            "--samples",
+            "10",
+            "--budget-ms",
+            "250",
+            "--baseline",
+            "b.toml",
+        ])
+        .unwrap();
+
+        assert_eq!(args.days, vec![8]);
+        assert_eq!(
+            args.command,
+            Command::Bench(BenchArgs {
+                config: BenchConfig {
+                    samples: 10,
+                    budget: Duration::from_millis(250),
+                    ..BenchConfig::default()
+                },
+                baseline: Some(PathBuf::from("b.toml")),
+                save_baseline: None,
+            })
+        );
+    }
+
+    #[test]
+    fn test_journal_commands() {
+        let args = parse(&["start", "12"]).unwrap();
+        assert_eq!((args.command, args.days), (Command::Start, vec![12]));
+
+        let args = parse(&["solve", "9", "-p", "2", "--note", "Finally."]).unwrap();
+        assert_eq!(
+            args.command,
+            Command::Solve {
+                note: Some("Finally.".to_string())
+            }
+        );
+        assert_eq!(args.days, vec![9]);
+        assert_eq!(args.options.part, Part::Two);
+
+        assert_eq!(parse(&["notes"]).unwrap().command, Command::Notes);
+    }
+
+    #[test]
+    fn test_fetch() {
+        let args = parse(&["fetch", "12", "--cache-dir", "/tmp/aoc", "--refresh"]).unwrap();
+
+        assert_eq!(args.days, vec![12]);
+        assert_eq!(
+            args.command,
+            Command::Fetch(FetchArgs {
+                cache_dir: PathBuf::from("/tmp/aoc"),
+                refresh: true,
+            })
+        );
+        assert!(parse(&["--refresh"]).is_err());
+
+        // Days that aren't solved yet can still be fetched, with or without naming them.
+        assert_eq!(
+            parse(&["fetch"]).unwrap().days,
+            (1..=12).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            parse(&["fetch", "all"]).unwrap().days,
+            (1..=12).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_submit() {
+        let args = parse(&["submit", "3", "-p", "2", "--answer", "1234"]).unwrap();
+
+        assert_eq!(args.days, vec![3]);
+        assert_eq!(args.options.part, Part::Two);
+        assert_eq!(
+            args.command,
+            Command::Submit(SubmitArgs {
+                answer: Some("1234".to_string()),
+                cache_dir: PathBuf::from(fetch::DEFAULT_CACHE_DIR),
+            })
+        );
+        assert!(parse(&["submit", "3"]).is_err());
+        assert!(parse(&["submit", "3-4", "-p", "1"]).is_err());
+        assert!(parse(&["--answer", "1234"]).is_err());
+        assert!(parse(&["bench", "--cache-dir", "/tmp/aoc"]).is_err());
+    }
+
+    #[test]
+    fn test_new() {
+        let args = parse(&["new", "12"]).unwrap();
+
+        assert_eq!((args.command, args.days), (Command::New, vec![12]));
+        assert!(parse(&["new"]).is_err());
+        assert!(parse(&["new", "13"]).is_err());
+    }
+
+    #[test]
+    fn test_help() {
+        let args = parse_args(["--help".to_string()], &DAYS).unwrap();
+
+        assert!(args.is_none());
+    }
+}
@@ -1,39 +1,95 @@
+use utilities::{Answer, Puzzle, PuzzleError, rng::Rng};
+
 const DAY: usize = 1;
 
-pub fn run() {
-    utilities::run_puzzle(DAY, part_1, part_2);
+pub struct Day1;
+
+impl Puzzle for Day1 {
+    type Input = Vec<Rotation>;
+
+    fn day(&self) -> usize {
+        DAY
+    }
+
+    fn title(&self) -> &'static str {
+        "Secret Entrance"
+    }
+
+    fn parse(&self, contents: &str) -> Result<Self::Input, PuzzleError> {
+        parse_rotations(contents)
+    }
+
+    fn part_1(&self, rotations: &Self::Input) -> Result<Answer, PuzzleError> {
+        Ok(part_1(rotations).to_string())
+    }
+
+    fn part_2(&self, rotations: &Self::Input) -> Result<Answer, PuzzleError> {
+        Ok(part_2(rotations).to_string())
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> Option<String> {
+        Some(generate_rotations(rng, size))
+    }
 }
 
 #[derive(Debug)]
-enum Rotation {
+pub enum Rotation {
     Left(usize),
     Right(usize),
 }
 
 impl Rotation {
-    fn new(text: &str) -> Self {
-        let direction = text.chars().nth(0).unwrap();
-        let distance = text[1..].parse::<usize>().unwrap();
+    fn new(line: usize, text: &str) -> Result<Self, PuzzleError> {
+        let Some(direction) = text.chars().next() else {
+            return Err(PuzzleError::new(line, 1, "missing rotation"));
+        };
+
+        let distance_text = &text[direction.len_utf8()..];
+        let distance = distance_text.parse::<usize>().map_err(|_| {
+            PuzzleError::at_token(
+                line,
+                text,
+                distance_text,
+                format!("invalid distance '{distance_text}'"),
+            )
+        })?;
 
         match direction {
-            'L' => Self::Left(distance),
-            'R' => Self::Right(distance),
-            _ => unreachable!(),
+            'L' => Ok(Self::Left(distance)),
+            'R' => Ok(Self::Right(distance)),
+            _ => Err(PuzzleError::new(
+                line,
+                1,
+                format!("unknown direction '{direction}'"),
+            )),
         }
     }
 }
 
-fn part_1(contents: &str) -> usize {
-    let rotations = parse_rotations(contents);
-
+fn part_1(rotations: &[Rotation]) -> usize {
     calculate_password(rotations)
 }
 
-fn parse_rotations(contents: &str) -> Vec<Rotation> {
-    contents.lines().map(Rotation::new).collect()
+fn parse_rotations(contents: &str) -> Result<Vec<Rotation>, PuzzleError> {
+    contents
+        .lines()
+        .enumerate()
+        .map(|(i, line)| Rotation::new(i + 1, line))
+        .collect()
+}
+
+/// `size` rotations of up to 999 clicks each.
+fn generate_rotations(rng: &mut Rng, size: usize) -> String {
+    (0..size)
+        .map(|_| {
+            let direction = if rng.chance(0.5) { 'L' } else { 'R' };
+
+            format!("{direction}{}\n", rng.range(1..=999))
+        })
+        .collect()
 }
 
-fn calculate_password(rotations: Vec<Rotation>) -> usize {
+fn calculate_password(rotations: &[Rotation]) -> usize {
     let (_, at_zero) = rotations
         .iter()
         .fold((50, 0), |(current, at_zero), rotation| {
@@ -52,18 +108,16 @@ fn calculate_password(rotations: Vec<Rotation>) -> usize {
     at_zero
 }
 
-fn part_2(contents: &str) -> usize {
-    let rotations = parse_rotations(contents);
-
+fn part_2(rotations: &[Rotation]) -> usize {
     calculate_password_v2(rotations)
 }
 
-fn calculate_password_v2(rotations: Vec<Rotation>) -> usize {
+fn calculate_password_v2(rotations: &[Rotation]) -> usize {
     let mut current = 50;
     let mut at_zero = 0;
 
     for rotation in rotations {
-        match rotation {
+        match *rotation {
             Rotation::Left(dist) => {
                 if current <= dist {
                     // Crossing zero, count any additional wraparounds, but make sure not to double count starting at 0.
@@ -98,31 +152,53 @@ fn calculate_password_v2(rotations: Vec<Rotation>) -> usize {
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_example_part_1() {
-        let contents = utilities::read_file_data(DAY, "example.txt");
+    use utilities::rng::for_each_generated;
 
-        assert_eq!(part_1(&contents), 3);
-    }
+    /// Turns the dial one click at a time, returning how often it stops at and passes zero.
+    fn click_through(rotations: &[Rotation]) -> (usize, usize) {
+        let (mut dial, mut stops, mut passes) = (50, 0, 0);
 
-    #[test]
-    fn test_input_part_1() {
-        let contents = utilities::read_file_data(DAY, "input.txt");
+        for rotation in rotations {
+            let (step, distance) = match *rotation {
+                Rotation::Left(distance) => (99, distance),
+                Rotation::Right(distance) => (1, distance),
+            };
 
-        assert_eq!(part_1(&contents), 1097);
+            for _ in 0..distance {
+                dial = (dial + step) % 100;
+                passes += usize::from(dial == 0);
+            }
+            stops += usize::from(dial == 0);
+        }
+
+        (stops, passes)
     }
 
     #[test]
-    fn test_example_part_2() {
-        let contents = utilities::read_file_data(DAY, "example.txt");
-
-        assert_eq!(part_2(&contents), 6);
+    fn test_generated_input() {
+        for_each_generated(
+            0..20,
+            200,
+            generate_rotations,
+            parse_rotations,
+            |_, rotations| {
+                assert_eq!(
+                    (part_1(&rotations), part_2(&rotations)),
+                    click_through(&rotations)
+                );
+            },
+        );
     }
 
     #[test]
-    fn test_input_part_2() {
-        let contents = utilities::read_file_data(DAY, "input.txt");
-
-        assert_eq!(part_2(&contents), 7101);
+    fn test_malformed_input() {
+        assert_eq!(
+            parse_rotations("L68\nX30").unwrap_err(),
+            PuzzleError::new(2, 1, "unknown direction 'X'")
+        );
+        assert_eq!(
+            parse_rotations("L68\nR3a").unwrap_err(),
+            PuzzleError::new(2, 2, "invalid distance '3a'")
+        );
     }
 }
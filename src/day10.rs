@@ -3,71 +3,185 @@ use std::{
     collections::{BinaryHeap, HashMap},
 };
 
+use utilities::{
+    Answer, Puzzle, PuzzleError,
+    parse::{
+        ParseResult, Span, delimited, integer, many, parse_lines, preceded, separated, spaces,
+        take_while,
+    },
+    rng::Rng,
+};
+
 const DAY: usize = 10;
 
-pub fn run() {
-    utilities::run_puzzle(DAY, part_1, part_2);
+pub struct Day10;
+
+impl Puzzle for Day10 {
+    type Input = Vec<Machine>;
+
+    fn day(&self) -> usize {
+        DAY
+    }
+
+    fn title(&self) -> &'static str {
+        "Factory"
+    }
+
+    fn parse(&self, contents: &str) -> Result<Self::Input, PuzzleError> {
+        parse_machines(contents)
+    }
+
+    fn part_1(&self, machines: &Self::Input) -> Result<Answer, PuzzleError> {
+        Ok(part_1(machines)?.to_string())
+    }
+
+    fn part_2(&self, machines: &Self::Input) -> Result<Answer, PuzzleError> {
+        Ok(part_2(machines)?.to_string())
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> Option<String> {
+        Some(generate_machines(rng, size))
+    }
 }
 
 #[derive(Debug)]
-struct Machine {
+pub struct Machine {
+    line: usize,
     lights: usize,
     buttons: Vec<usize>,
     joltages: Vec<usize>,
 }
 
 impl Machine {
-    fn new(text: &str) -> Self {
-        let pieces = text.split_ascii_whitespace().collect::<Vec<_>>();
+    fn parse(input: Span) -> ParseResult<Self> {
+        let (line, start) = (input.line, input);
+
+        let (lights, after_lights) =
+            delimited("[", take_while(|c| c == '.' || c == '#'), "]")(input)?;
+        if lights.len() > usize::BITS as usize {
+            return Err(input.error(format!("more than {} lights", usize::BITS)));
+        }
+
+        let (groups, input) = many(preceded(spaces, Machine::parse_group))(after_lights)?;
+        // Every combination of buttons gets a bit in a mask, with one value past the last to spare.
+        if groups.len() >= usize::BITS as usize {
+            return Err(start.error(format!("more than {} buttons", usize::BITS - 1)));
+        }
+        let (_, joltages_start) = spaces(input)?;
+        let (joltages, input) = delimited("{", separated(integer, ","), "}")(joltages_start)?;
+
+        if joltages.len() != lights.len() {
+            return Err(
+                joltages_start.error(format!("expected {} joltages, one per light", lights.len()))
+            );
+        }
 
-        let lights = pieces[0]
+        let buttons = groups
+            .into_iter()
+            .map(|group| {
+                group.into_iter().try_fold(0, |button, (index, at)| {
+                    if index < lights.len() {
+                        Ok(button | 1 << index)
+                    } else {
+                        Err(at.error(format!("light {index} is out of range")))
+                    }
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let lights = lights
             .chars()
             .rev()
-            .skip(1)
-            .take(pieces[0].len() - 2)
             .fold(0, |acc, c| (acc << 1) + if c == '#' { 1 } else { 0 });
 
-        let buttons = pieces
-            .iter()
-            .skip(1)
-            .take(pieces.len() - 2)
-            .map(|s| Machine::parse_group(s))
-            .collect();
-
-        let joltage = pieces[pieces.len() - 1];
-        let joltages = joltage[1..joltage.len() - 1]
-            .split(",")
-            .map(|s| s.parse::<usize>().unwrap())
-            .collect();
-
-        Self {
-            lights,
-            buttons,
-            joltages,
-        }
+        Ok((
+            Self {
+                line,
+                lights,
+                buttons,
+                joltages,
+            },
+            input,
+        ))
     }
 
-    fn parse_group(text: &str) -> usize {
-        let len = text.len();
+    /// The light indices a button is wired to, each with where it was written.
+    fn parse_group(input: Span) -> ParseResult<Vec<(usize, Span)>> {
+        let index = |input| integer(input).map(|(index, rest)| ((index, input), rest));
 
-        text[1..(len - 1)]
-            .split(",")
-            .map(|s| 1 << s.parse::<usize>().unwrap())
-            .sum()
+        delimited("(", separated(index, ","), ")")(input)
     }
 }
 
-fn part_1(contents: &str) -> usize {
-    let machines = parse_machines(contents);
+fn part_1(machines: &[Machine]) -> Result<usize, PuzzleError> {
+    machines.iter().map(calculate_presses).sum()
+}
+
+fn parse_machines(contents: &str) -> Result<Vec<Machine>, PuzzleError> {
+    parse_lines(contents, Machine::parse)
+}
+
+/// `size` machines with 3 to 10 lights and 3 to 10 buttons, each solvable in both parts.
+fn generate_machines(rng: &mut Rng, size: usize) -> String {
+    (0..size)
+        .map(|_| {
+            let lights = rng.range(3..=10);
+            let buttons = rng.range(3..=10);
 
-    machines.into_iter().map(calculate_presses).sum()
+            random_machine(rng, lights, buttons, 12) + "\n"
+        })
+        .collect()
 }
 
-fn parse_machines(contents: &str) -> Vec<Machine> {
-    contents.lines().map(Machine::new).collect()
+/// A machine whose lights come from pressing some of its buttons once, and whose joltages come
+/// from pressing each button up to `max_presses` times.
+fn random_machine(rng: &mut Rng, lights: usize, buttons: usize, max_presses: usize) -> String {
+    let buttons = (0..buttons)
+        .map(|_| {
+            let mut wired = (0..lights).collect::<Vec<_>>();
+            rng.shuffle(&mut wired);
+            wired.truncate(rng.range(1..=lights - 1));
+            wired.sort();
+
+            wired
+        })
+        .collect::<Vec<_>>();
+
+    let mut on = vec![false; lights];
+    let mut joltages = vec![0; lights];
+
+    for button in &buttons {
+        let pressed = rng.chance(0.5);
+        let presses = rng.range(0..=max_presses);
+
+        for light in button {
+            on[*light] ^= pressed;
+            joltages[*light] += presses;
+        }
+    }
+
+    let lights = on
+        .iter()
+        .map(|on| if *on { '#' } else { '.' })
+        .collect::<String>();
+    let buttons = buttons
+        .iter()
+        .map(|button| {
+            let wired = button.iter().map(usize::to_string).collect::<Vec<_>>();
+
+            format!("({})", wired.join(","))
+        })
+        .collect::<Vec<_>>();
+    let joltages = joltages.iter().map(usize::to_string).collect::<Vec<_>>();
+
+    format!(
+        "[{lights}] {} {{{}}}",
+        buttons.join(" "),
+        joltages.join(",")
+    )
 }
 
-fn calculate_presses(machine: Machine) -> usize {
+fn calculate_presses(machine: &Machine) -> Result<usize, PuzzleError> {
     let mut queue = BinaryHeap::new();
     let mut presses = HashMap::new();
 
@@ -75,7 +189,7 @@ fn calculate_presses(machine: Machine) -> usize {
 
     while let Some(Reverse((num_pushes, state))) = queue.pop() {
         if state == machine.lights {
-            return num_pushes;
+            return Ok(num_pushes);
         }
 
         if let Some(best_count) = presses.get(&state)
@@ -91,21 +205,31 @@ fn calculate_presses(machine: Machine) -> usize {
         }
     }
 
-    unreachable!()
+    Err(PuzzleError::new(
+        machine.line,
+        1,
+        "no combination of buttons turns on the lights",
+    ))
 }
 
-fn part_2(contents: &str) -> usize {
-    let machines = parse_machines(contents);
-
-    machines.into_iter().map(calculate_presses_joltage_v3).sum()
+fn part_2(machines: &[Machine]) -> Result<usize, PuzzleError> {
+    machines.iter().map(calculate_presses_joltage_v3).sum()
 }
 
-fn calculate_presses_joltage_v3(machine: Machine) -> usize {
+fn calculate_presses_joltage_v3(machine: &Machine) -> Result<usize, PuzzleError> {
     // From the subreddit: https://www.reddit.com/r/adventofcode/comments/1pk87hl/2025_day_10_part_2_bifurcate_your_way_to_victory/
 
     let button_cache = build_button_cache(&machine.buttons);
     let mut cache = HashMap::new();
-    calculate_presses_joltage_helper(&button_cache, &machine.joltages, &mut cache).unwrap()
+    calculate_presses_joltage_helper(&button_cache, &machine.joltages, &mut cache).ok_or_else(
+        || {
+            PuzzleError::new(
+                machine.line,
+                1,
+                "no combination of button presses reaches the joltages",
+            )
+        },
+    )
 }
 
 fn build_lights(joltages: &[usize]) -> usize {
@@ -202,31 +326,121 @@ fn calculate_presses_joltage_helper(
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_example_part_1() {
-        let contents = utilities::read_file_data(DAY, "example.txt");
+    use utilities::rng::for_each_generated;
 
-        assert_eq!(part_1(&contents), 7);
+    #[test]
+    fn test_malformed_input() {
+        assert_eq!(
+            parse_machines("[.##.] (3) (1,x) {3,5,4,7}").unwrap_err(),
+            PuzzleError::new(1, 12, "expected '{', found '('")
+        );
+        assert_eq!(
+            parse_machines("[.##.] (3) (1,3)").unwrap_err(),
+            PuzzleError::new(1, 17, "expected '{', found end of input")
+        );
+        assert_eq!(
+            parse_machines("[.#] (1) {0,1}\n[.#] (70) {1,2}").unwrap_err(),
+            PuzzleError::new(2, 7, "light 70 is out of range")
+        );
+        assert_eq!(
+            parse_machines("[.#] (0) (1,5) {1,2}").unwrap_err(),
+            PuzzleError::new(1, 13, "light 5 is out of range")
+        );
+        assert_eq!(
+            parse_machines("[.#] (0) {1,2,3}").unwrap_err(),
+            PuzzleError::new(1, 10, "expected 2 joltages, one per light")
+        );
+        assert_eq!(
+            parse_machines(&format!("[{}] (0) {{0}}", ".".repeat(65))).unwrap_err(),
+            PuzzleError::new(1, 1, "more than 64 lights")
+        );
+        assert_eq!(
+            parse_machines(&format!(
+                "[.#] (0) {{0,0}}\n[.#]{} {{0,0}}",
+                " (1)".repeat(64)
+            ))
+            .unwrap_err(),
+            PuzzleError::new(2, 1, "more than 63 buttons")
+        );
     }
 
     #[test]
-    fn test_input_part_1() {
-        let contents = utilities::read_file_data(DAY, "input.txt");
-
-        assert_eq!(part_1(&contents), 486);
+    fn test_unsolvable_machines() {
+        let machines = parse_machines("[.#] (1) {1,2}\n[##] (0) {1,2}").unwrap();
+
+        assert_eq!(
+            part_1(&machines),
+            Err(PuzzleError::new(
+                2,
+                1,
+                "no combination of buttons turns on the lights"
+            ))
+        );
+        assert_eq!(
+            part_2(&machines),
+            Err(PuzzleError::new(
+                1,
+                1,
+                "no combination of button presses reaches the joltages"
+            ))
+        );
     }
 
-    #[test]
-    fn test_example_part_2() {
-        let contents = utilities::read_file_data(DAY, "example.txt");
+    /// The fewest presses in total that leave each counter at its joltage, trying every count for
+    /// every button.
+    fn fewest_presses(buttons: &[usize], joltages: &[usize]) -> Option<usize> {
+        let Some((button, rest)) = buttons.split_first() else {
+            return joltages.iter().all(|joltage| *joltage == 0).then_some(0);
+        };
+
+        let wired = bits_to_indices(*button);
+        let most = wired.iter().map(|index| joltages[*index]).min().unwrap();
+
+        (0..=most)
+            .filter_map(|presses| {
+                let mut remaining = joltages.to_vec();
+                wired.iter().for_each(|index| remaining[*index] -= presses);
 
-        assert_eq!(part_2(&contents), 33);
+                fewest_presses(rest, &remaining).map(|fewest| fewest + presses)
+            })
+            .min()
     }
 
     #[test]
-    fn test_input_part_2() {
-        let contents = utilities::read_file_data(DAY, "input.txt");
-
-        assert_eq!(part_2(&contents), 17820);
+    fn test_generated_input() {
+        // Small machines, so every combination of button presses can be tried.
+        let small_machine = |rng: &mut Rng, size| random_machine(rng, size, size, 3);
+
+        for_each_generated(0..50, 4, small_machine, parse_machines, |_, machines| {
+            let machine = &machines[0];
+
+            let fewest_toggles = (0..1usize << machine.buttons.len())
+                .filter(|pressed| {
+                    bits_to_indices(*pressed)
+                        .iter()
+                        .fold(0, |lights, index| lights ^ machine.buttons[*index])
+                        == machine.lights
+                })
+                .map(|pressed| pressed.count_ones() as usize)
+                .min();
+
+            assert_eq!(part_1(&machines).ok(), fewest_toggles);
+            assert_eq!(
+                part_2(&machines).ok(),
+                fewest_presses(&machine.buttons, &machine.joltages)
+            );
+        });
+
+        for_each_generated(
+            0..5,
+            20,
+            generate_machines,
+            parse_machines,
+            |_, machines| {
+                assert_eq!(machines.len(), 20);
+                assert!(part_1(&machines).is_ok());
+                assert!(part_2(&machines).is_ok());
+            },
+        );
     }
 }
@@ -1,89 +1,280 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+
+use utilities::{
+    Answer, Puzzle, PuzzleError,
+    graph::{Graph, NodeId},
+    rng::Rng,
+};
 
 const DAY: usize = 11;
 
-pub fn run() {
-    utilities::run_puzzle(DAY, part_1, part_2);
-}
+pub struct Day11;
+
+impl Puzzle for Day11 {
+    type Input = ServerRack;
+
+    fn day(&self) -> usize {
+        DAY
+    }
+
+    fn title(&self) -> &'static str {
+        "Reactor"
+    }
+
+    fn parse(&self, contents: &str) -> Result<Self::Input, PuzzleError> {
+        parse_server_rack(contents)
+    }
+
+    fn part_1(&self, server_rack: &Self::Input) -> Result<Answer, PuzzleError> {
+        Ok(part_1(server_rack)?.to_string())
+    }
 
-type ServerRack = HashMap<String, Vec<String>>;
+    fn part_2(&self, server_rack: &Self::Input) -> Result<Answer, PuzzleError> {
+        Ok(part_2(server_rack)?.to_string())
+    }
 
-fn part_1(contents: &str) -> usize {
-    let server_rack = parse_server_rack(contents);
+    fn generate(&self, rng: &mut Rng, size: usize) -> Option<String> {
+        Some(generate_server_rack(rng, size))
+    }
+}
 
-    count_paths(&server_rack, &"you".to_string())
+pub struct ServerRack {
+    devices: Graph,
+    /// The line listing each device's outputs, for pointing at the devices in a loop.
+    lines: HashMap<NodeId, usize>,
 }
 
-fn parse_server_rack(contents: &str) -> ServerRack {
-    let mut server_rack: ServerRack = HashMap::new();
+fn part_1(server_rack: &ServerRack) -> Result<usize, PuzzleError> {
+    count_paths(server_rack, "you", "out", &[])
+}
 
-    contents.lines().for_each(|line| {
-        let mut pieces = line.split(":");
-        let server = pieces.next().unwrap();
+fn parse_server_rack(contents: &str) -> Result<ServerRack, PuzzleError> {
+    let mut devices = Graph::new();
+    let mut lines = HashMap::new();
 
-        let targets = pieces
-            .next()
-            .unwrap()
-            .split_ascii_whitespace()
-            .filter(|text| !text.is_empty())
-            .map(|s| s.to_string())
-            .collect::<Vec<_>>();
+    for (i, line) in contents.lines().enumerate() {
+        let Some((server, targets)) = line.split_once(':') else {
+            return Err(PuzzleError::at_token(
+                i + 1,
+                line,
+                line,
+                "expected 'server: outputs'",
+            ));
+        };
 
-        server_rack
-            .entry(server.to_string())
-            .and_modify(|v| v.extend(targets.clone()))
-            .or_insert(targets);
-    });
+        let server = devices.intern(server);
+        lines.entry(server).or_insert(i + 1);
 
-    server_rack
+        for target in targets.split_ascii_whitespace() {
+            let target = devices.intern(target);
+            devices.add_edge(server, target);
+        }
+    }
+
+    Ok(ServerRack { devices, lines })
 }
 
-fn count_paths(server_rack: &ServerRack, current: &String) -> usize {
-    if current == "out" {
-        return 1;
+/// `size` devices (at least five) wired into a DAG that ends at `out`. `svr` comes first, `you` is
+/// among the last fifteen, and `dac` and `fft` are somewhere in between. Each device feeds the next
+/// one, so every device is on some path, and about thirty of them also feed one or two devices a
+/// little further along, which keeps the number of paths within a `usize`.
+fn generate_server_rack(rng: &mut Rng, size: usize) -> String {
+    let size = size.max(5);
+
+    let mut names = vec!["svr".to_string()];
+    let mut used = HashSet::from(["svr", "you", "dac", "fft", "out"].map(str::to_string));
+
+    while names.len() < size {
+        let name = (0..3)
+            .map(|_| char::from(b'a' + rng.below(26) as u8))
+            .collect::<String>();
+
+        if used.insert(name.clone()) {
+            names.push(name);
+        }
     }
+    names.push("out".to_string());
 
-    server_rack
-        .get(current)
-        .unwrap()
+    let you = rng.range(size.saturating_sub(15).max(1)..=size - 1);
+    let mut middle = (1..size).filter(|i| *i != you).collect::<Vec<_>>();
+    rng.shuffle(&mut middle);
+    names[you] = "you".to_string();
+    names[middle[0]] = "dac".to_string();
+    names[middle[1]] = "fft".to_string();
+
+    let branching = (30.0 / size as f64).min(0.5);
+
+    (0..size)
+        .map(|i| {
+            let reach = (i + 1)..=(i + 6).min(size);
+            let shortcuts = if rng.chance(branching) {
+                rng.range(1..=2)
+            } else {
+                0
+            };
+
+            let mut targets = std::iter::once(i + 1)
+                .chain((0..shortcuts).map(|_| rng.range(reach.clone())))
+                .collect::<Vec<_>>();
+            targets.sort();
+            targets.dedup();
+
+            let targets = targets
+                .iter()
+                .map(|target| names[*target].as_str())
+                .collect::<Vec<_>>();
+
+            format!("{}: {}\n", names[i], targets.join(" "))
+        })
+        .collect()
+}
+
+/// Counts the paths from `from` to `to` that visit every device in `via`, in any order. A device
+/// the input never mentions has no paths to or from it. Fails if the devices on those paths are
+/// wired in a loop, since there would be no end to the paths around it, pointing at the line of
+/// the first device in the loop.
+fn count_paths(
+    server_rack: &ServerRack,
+    from: &str,
+    to: &str,
+    via: &[&str],
+) -> Result<usize, PuzzleError> {
+    let ids = [from, to]
         .iter()
-        .map(|destination| count_paths(server_rack, destination))
-        .sum()
+        .chain(via)
+        .map(|device| server_rack.devices.id(device))
+        .collect::<Option<Vec<_>>>();
+
+    let Some(ids) = ids else {
+        return Ok(0);
+    };
+
+    server_rack
+        .devices
+        .count_paths_via(ids[0], ids[1], &ids[2..])
+        .map_err(|cycle| {
+            let mut devices = cycle
+                .nodes
+                .iter()
+                .map(|id| server_rack.devices.name(*id))
+                .collect::<Vec<_>>();
+            devices.push(devices[0]);
+
+            // Every device in a loop has outputs, so it has a line of its own.
+            PuzzleError::new(
+                server_rack.lines[&cycle.nodes[0]],
+                1,
+                format!("devices are wired in a loop: {}", devices.join(" -> ")),
+            )
+        })
 }
 
-fn part_2(contents: &str) -> usize {
-    0
+fn part_2(server_rack: &ServerRack) -> Result<usize, PuzzleError> {
+    count_paths(server_rack, "svr", "out", &["dac", "fft"])
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    use utilities::rng::for_each_generated;
+
     #[test]
-    fn test_example_part_1() {
-        let contents = utilities::read_file_data(DAY, "example.txt");
+    fn test_count_paths_via() {
+        let contents = utilities::read_file_data(DAY, "example2.txt");
+        let server_rack = parse_server_rack(&contents).unwrap();
 
-        assert_eq!(part_1(&contents), 5);
+        assert_eq!(count_paths(&server_rack, "svr", "out", &[]), Ok(8));
+        assert_eq!(count_paths(&server_rack, "svr", "out", &["fft"]), Ok(4));
+        assert_eq!(count_paths(&server_rack, "svr", "fff", &["hub"]), Ok(2));
+        assert_eq!(count_paths(&server_rack, "svr", "nowhere", &[]), Ok(0));
     }
 
     #[test]
-    fn test_input_part_1() {
-        let contents = utilities::read_file_data(DAY, "input.txt");
+    fn test_count_paths_reports_loops() {
+        let server_rack =
+            parse_server_rack("you: aaa\naaa: bbb out\nbbb: ccc\nccc: aaa\n").unwrap();
+
+        assert_eq!(
+            count_paths(&server_rack, "you", "out", &[]),
+            Err(PuzzleError::new(
+                2,
+                1,
+                "devices are wired in a loop: aaa -> bbb -> ccc -> aaa"
+            ))
+        );
 
-        assert_eq!(part_1(&contents), 574);
+        // A loop that no path to out goes through doesn't get in the way.
+        let server_rack = parse_server_rack("you: out aaa\naaa: aaa\n").unwrap();
+        assert_eq!(count_paths(&server_rack, "you", "out", &[]), Ok(1));
     }
 
-    #[test]
-    fn test_example_part_2() {
-        let contents = utilities::read_file_data(DAY, "example2.txt");
+    /// Counts paths from `device` to `to` with every device's count worked out once.
+    fn count_paths_memoized(
+        server_rack: &ServerRack,
+        device: &str,
+        to: &str,
+        counts: &mut HashMap<String, usize>,
+    ) -> usize {
+        if device == to {
+            return 1;
+        }
+
+        if let Some(count) = counts.get(device) {
+            return *count;
+        }
+
+        let devices = &server_rack.devices;
+        let count = devices
+            .successors(devices.id(device).unwrap())
+            .iter()
+            .map(|output| count_paths_memoized(server_rack, devices.name(*output), to, counts))
+            .sum();
+        counts.insert(device.to_string(), count);
+
+        count
+    }
+
+    /// The paths from svr to out through dac and fft. Without loops, dac and fft are visited in
+    /// one order or the other.
+    fn count_via_dac_and_fft(server_rack: &ServerRack) -> usize {
+        let count =
+            |from: &str, to: &str| count_paths_memoized(server_rack, from, to, &mut HashMap::new());
 
-        assert_eq!(part_2(&contents), 0);
+        count("svr", "dac") * count("dac", "fft") * count("fft", "out")
+            + count("svr", "fft") * count("fft", "dac") * count("dac", "out")
     }
 
     #[test]
-    fn test_input_part_2() {
-        let contents = utilities::read_file_data(DAY, "input.txt");
+    fn test_generated_input() {
+        for_each_generated(
+            0..20,
+            300,
+            generate_server_rack,
+            parse_server_rack,
+            |_, server_rack| {
+                // Every device has a line, apart from out.
+                assert_eq!(server_rack.devices.len(), 301);
+                assert!(
+                    ["svr", "you", "dac", "fft"]
+                        .iter()
+                        .all(|device| server_rack.devices.id(device).is_some())
+                );
 
-        assert_eq!(part_2(&contents), 0);
+                assert_eq!(
+                    part_1(&server_rack),
+                    Ok(count_paths_memoized(
+                        &server_rack,
+                        "you",
+                        "out",
+                        &mut HashMap::new()
+                    ))
+                );
+                assert_eq!(
+                    part_2(&server_rack),
+                    Ok(count_via_dac_and_fft(&server_rack))
+                );
+            },
+        );
     }
 }
@@ -1,32 +1,62 @@
 use std::{cmp::Ordering, collections::HashSet, ops::RangeInclusive};
 
+use utilities::{
+    Answer, Puzzle, PuzzleError,
+    parse::{integer, parse_all, range, separated},
+    rng::Rng,
+};
+
 const DAY: usize = 2;
 
-pub fn run() {
-    utilities::run_puzzle(DAY, part_1, part_2);
+pub struct Day2;
+
+impl Puzzle for Day2 {
+    type Input = Vec<RangeInclusive<usize>>;
+
+    fn day(&self) -> usize {
+        DAY
+    }
+
+    fn title(&self) -> &'static str {
+        "Gift Shop"
+    }
+
+    fn parse(&self, contents: &str) -> Result<Self::Input, PuzzleError> {
+        parse_ranges(contents)
+    }
+
+    fn part_1(&self, ranges: &Self::Input) -> Result<Answer, PuzzleError> {
+        Ok(part_1(ranges).to_string())
+    }
+
+    fn part_2(&self, ranges: &Self::Input) -> Result<Answer, PuzzleError> {
+        Ok(part_2(ranges).to_string())
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> Option<String> {
+        Some(generate_ranges(rng, size))
+    }
 }
 
-fn part_1(contents: &str) -> usize {
-    let ranges = parse_ranges(contents);
+fn part_1(ranges: &[RangeInclusive<usize>]) -> usize {
+    ranges.iter().map(find_invalid_ids).sum()
+}
 
-    ranges
-        .into_iter()
-        .map(|range| find_invalid_ids(&range))
-        .sum()
+fn parse_ranges(contents: &str) -> Result<Vec<RangeInclusive<usize>>, PuzzleError> {
+    parse_all(contents, separated(range(integer, "-"), ","))
 }
 
-fn parse_ranges(contents: &str) -> Vec<RangeInclusive<usize>> {
-    contents
-        .split(",")
-        .map(|s| {
-            let pieces = s
-                .split("-")
-                .map(|v| v.parse::<usize>().unwrap())
-                .collect::<Vec<_>>();
+/// `size` comma-separated ID ranges of up to a thousand IDs each, with IDs of one to ten digits.
+fn generate_ranges(rng: &mut Rng, size: usize) -> String {
+    (0..size)
+        .map(|_| {
+            let digits = rng.range(1..=10) as u32;
+            let start = rng.range(10usize.pow(digits - 1)..=10usize.pow(digits) - 1);
 
-            pieces[0]..=pieces[1]
+            format!("{start}-{}", start + rng.range(0..=1000))
         })
-        .collect()
+        .collect::<Vec<_>>()
+        .join(",")
 }
 
 fn find_invalid_ids(range: &RangeInclusive<usize>) -> usize {
@@ -49,7 +79,7 @@ fn split_number(val: usize, is_start: bool) -> usize {
     let digits = val.to_string();
     let len = digits.len();
 
-    if len % 2 == 0 {
+    if len.is_multiple_of(2) {
         let prefix = digits[0..(len / 2)].parse::<usize>().unwrap();
 
         match (is_start, make_invalid_id(prefix, 2).cmp(&val)) {
@@ -64,18 +94,14 @@ fn split_number(val: usize, is_start: bool) -> usize {
     }
 }
 
-fn part_2(contents: &str) -> usize {
-    let ranges = parse_ranges(contents);
-
-    ranges
-        .into_iter()
-        .map(|range| find_invalid_ids_v2(&range))
-        .sum()
+fn part_2(ranges: &[RangeInclusive<usize>]) -> usize {
+    ranges.iter().map(find_invalid_ids_v2).sum()
 }
 
 fn find_invalid_ids_v2(range: &RangeInclusive<usize>) -> usize {
-    let start_digits = range.start().ilog10() + 1;
-    let end_digits = range.end().ilog10() + 1;
+    // Zero has no logarithm but is still written with one digit.
+    let start_digits = range.start().checked_ilog10().unwrap_or(0) + 1;
+    let end_digits = range.end().checked_ilog10().unwrap_or(0) + 1;
 
     let max_prefix_digits = start_digits.max(end_digits) / 2;
 
@@ -115,38 +141,43 @@ fn make_invalid_id(prefix: usize, scale: usize) -> usize {
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_example_part_1() {
-        let contents = utilities::read_file_data(DAY, "example.txt");
+    use utilities::rng::for_each_generated;
 
-        assert_eq!(part_1(&contents), 1227775554);
-    }
-
-    #[test]
-    fn test_example2_part_1() {
-        let contents = "1-19";
+    /// Whether `id` is some sequence of digits repeated `times` times, or at least twice if `None`.
+    fn is_repeated(id: usize, times: Option<usize>) -> bool {
+        let digits = id.to_string();
 
-        assert_eq!(part_1(&contents), 11);
-    }
-
-    #[test]
-    fn test_input_part_1() {
-        let contents = utilities::read_file_data(DAY, "input.txt");
-
-        assert_eq!(part_1(&contents), 23560874270);
+        (1..digits.len()).any(|size| {
+            digits.len().is_multiple_of(size)
+                && times.is_none_or(|times| digits.len() / size == times)
+                && digits
+                    .as_bytes()
+                    .chunks(size)
+                    .all(|chunk| chunk == &digits.as_bytes()[..size])
+        })
     }
 
     #[test]
-    fn test_example_part_2() {
-        let contents = utilities::read_file_data(DAY, "example.txt");
+    fn test_ranges_from_zero() {
+        let ranges = parse_ranges("0-5,0-22").unwrap();
 
-        assert_eq!(part_2(&contents), 4174379265);
+        assert_eq!(part_1(&ranges), 33);
+        assert_eq!(part_2(&ranges), 33);
     }
 
     #[test]
-    fn test_input_part_2() {
-        let contents = utilities::read_file_data(DAY, "input.txt");
-
-        assert_eq!(part_2(&contents), 44143124633);
+    fn test_generated_input() {
+        for_each_generated(0..10, 30, generate_ranges, parse_ranges, |_, ranges| {
+            let sum_where = |times| {
+                ranges
+                    .iter()
+                    .flat_map(|range| range.clone())
+                    .filter(|id| is_repeated(*id, times))
+                    .sum::<usize>()
+            };
+
+            assert_eq!(part_1(&ranges), sum_where(Some(2)));
+            assert_eq!(part_2(&ranges), sum_where(None));
+        });
     }
 }
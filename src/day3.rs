@@ -1,35 +1,66 @@
+use utilities::{Answer, Puzzle, PuzzleError, rng::Rng};
+
 const DAY: usize = 3;
 
-pub fn run() {
-    utilities::run_puzzle(DAY, part_1, part_2);
-}
+pub struct Day3;
+
+impl Puzzle for Day3 {
+    type Input = Vec<Vec<usize>>;
+
+    fn day(&self) -> usize {
+        DAY
+    }
+
+    fn title(&self) -> &'static str {
+        "Lobby"
+    }
+
+    fn parse(&self, contents: &str) -> Result<Self::Input, PuzzleError> {
+        parse_banks(contents)
+    }
+
+    fn part_1(&self, battery_banks: &Self::Input) -> Result<Answer, PuzzleError> {
+        Ok(part_1(battery_banks).to_string())
+    }
+
+    fn part_2(&self, battery_banks: &Self::Input) -> Result<Answer, PuzzleError> {
+        Ok(part_2(battery_banks).to_string())
+    }
 
-fn part_1(contents: &str) -> usize {
-    let battery_banks = parse_banks(contents);
+    fn generate(&self, rng: &mut Rng, size: usize) -> Option<String> {
+        Some(generate_banks(rng, size))
+    }
+}
 
+fn part_1(battery_banks: &[Vec<usize>]) -> usize {
     battery_banks
-        .into_iter()
-        .map(|bank| calculate_joltage(&bank, 2))
+        .iter()
+        .map(|bank| calculate_joltage(bank, 2))
         .sum()
 }
 
-fn parse_banks(contents: &str) -> Vec<Vec<usize>> {
-    contents
-        .lines()
-        .map(|line| {
-            line.chars()
-                .map(|c| c.to_digit(10).unwrap() as usize)
-                .collect()
+fn parse_banks(contents: &str) -> Result<Vec<Vec<usize>>, PuzzleError> {
+    utilities::parse::grid(contents, |c| c.to_digit(10).map(|d| d as usize))
+}
+
+/// `size` banks of 12 to 100 batteries rated 1 to 9.
+fn generate_banks(rng: &mut Rng, size: usize) -> String {
+    (0..size)
+        .map(|_| {
+            let batteries = rng.range(12..=100);
+
+            (0..batteries)
+                .map(|_| char::from(b'0' + rng.range(1..=9) as u8))
+                .chain(['\n'])
+                .collect::<String>()
         })
         .collect()
 }
 
-fn part_2(contents: &str) -> usize {
-    let battery_banks = parse_banks(contents);
-
+fn part_2(battery_banks: &[Vec<usize>]) -> usize {
     battery_banks
-        .into_iter()
-        .map(|bank| calculate_joltage(&bank, 12))
+        .iter()
+        .map(|bank| calculate_joltage(bank, 12))
         .sum()
 }
 
@@ -88,31 +119,31 @@ fn calculate_joltage_helper(
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_example_part_1() {
-        let contents = utilities::read_file_data(DAY, "example.txt");
+    use utilities::rng::for_each_generated;
 
-        assert_eq!(part_1(&contents), 357);
-    }
-
-    #[test]
-    fn test_input_part_1() {
-        let contents = utilities::read_file_data(DAY, "input.txt");
+    /// The largest number made of `count` batteries in order, picking each digit greedily.
+    fn greedy_joltage(bank: &[usize], count: usize) -> usize {
+        let mut start = 0;
+        let mut joltage = 0;
 
-        assert_eq!(part_1(&contents), 17207);
-    }
+        for remaining in (0..count).rev() {
+            let window = &bank[start..bank.len() - remaining];
+            let best = *window.iter().max().unwrap();
 
-    #[test]
-    fn test_example_part_2() {
-        let contents = utilities::read_file_data(DAY, "example.txt");
+            start += window.iter().position(|battery| *battery == best).unwrap() + 1;
+            joltage = joltage * 10 + best;
+        }
 
-        assert_eq!(part_2(&contents), 3121910778619);
+        joltage
     }
 
     #[test]
-    fn test_input_part_2() {
-        let contents = utilities::read_file_data(DAY, "input.txt");
+    fn test_generated_input() {
+        for_each_generated(0..10, 50, generate_banks, parse_banks, |_, banks| {
+            let sum_of = |count| banks.iter().map(|bank| greedy_joltage(bank, count)).sum();
 
-        assert_eq!(part_2(&contents), 170997883706617);
+            assert_eq!(part_1(&banks), sum_of(2));
+            assert_eq!(part_2(&banks), sum_of(12));
+        });
     }
 }
@@ -1,75 +1,73 @@
+use utilities::{Answer, Grid, Puzzle, PuzzleError, rng::Rng};
+
 const DAY: usize = 4;
 
-pub fn run() {
-    utilities::run_puzzle(DAY, part_1, part_2);
-}
+pub struct Day4;
 
-type Grid = Vec<Vec<char>>;
+impl Puzzle for Day4 {
+    type Input = Grid<char>;
 
-fn part_1(contents: &str) -> usize {
-    let grid = parse_grid(contents);
+    fn day(&self) -> usize {
+        DAY
+    }
 
-    count_rolls(grid)
-}
+    fn title(&self) -> &'static str {
+        "Printing Department"
+    }
 
-fn parse_grid(contents: &str) -> Grid {
-    contents
-        .lines()
-        .map(|line| line.chars().collect())
-        .collect()
-}
+    fn parse(&self, contents: &str) -> Result<Self::Input, PuzzleError> {
+        parse_grid(contents)
+    }
 
-fn count_rolls(grid: Grid) -> usize {
-    let mut free_rolls = 0;
+    fn part_1(&self, grid: &Self::Input) -> Result<Answer, PuzzleError> {
+        Ok(part_1(grid).to_string())
+    }
 
-    for r in 0..grid.len() {
-        for c in 0..grid[0].len() {
-            if grid[r][c] != '@' {
-                continue;
-            }
+    fn part_2(&self, grid: &Self::Input) -> Result<Answer, PuzzleError> {
+        Ok(part_2(grid).to_string())
+    }
 
-            if count_surrounding_rolls(&grid, r, c) < 4 {
-                free_rolls += 1;
-            }
-        }
+    fn generate(&self, rng: &mut Rng, size: usize) -> Option<String> {
+        Some(generate_grid(rng, size))
     }
+}
 
-    free_rolls
+fn part_1(grid: &Grid<char>) -> usize {
+    count_rolls(grid)
 }
 
-fn count_surrounding_rolls(grid: &Grid, row: usize, col: usize) -> usize {
-    let max_row = grid.len() - 1;
-    let max_col = grid[0].len() - 1;
-
-    let mut surrounding_rolls = 0;
-
-    for (r, row_data) in grid
-        .iter()
-        .enumerate()
-        .take(max_row.min(row + 1) + 1)
-        .skip(row.saturating_sub(1))
-    {
-        for (c, item) in row_data
-            .iter()
-            .enumerate()
-            .take(max_col.min(col + 1) + 1)
-            .skip(col.saturating_sub(1))
-        {
-            if r == row && c == col {
-                continue;
-            }
+fn parse_grid(contents: &str) -> Result<Grid<char>, PuzzleError> {
+    Grid::parse(contents, |c| matches!(c, '@' | '.').then_some(c))
+}
 
-            if item == &'@' {
-                surrounding_rolls += 1;
-            }
-        }
-    }
+/// A `size` by `size` grid, filled with rolls at a random density between 40% and 80%.
+fn generate_grid(rng: &mut Rng, size: usize) -> String {
+    let density = rng.range(40..=80) as f64 / 100.0;
+
+    (0..size)
+        .map(|_| {
+            (0..size)
+                .map(|_| if rng.chance(density) { '@' } else { '.' })
+                .chain(['\n'])
+                .collect::<String>()
+        })
+        .collect()
+}
+
+fn count_rolls(grid: &Grid<char>) -> usize {
+    grid.find_all(&'@')
+        .filter(|position| count_surrounding_rolls(grid, *position) < 4)
+        .count()
+}
 
-    surrounding_rolls
+fn count_surrounding_rolls(grid: &Grid<char>, position: (usize, usize)) -> usize {
+    grid.neighbors_8(position)
+        .filter(|neighbor| grid[*neighbor] == '@')
+        .count()
 }
 
-fn part_2(contents: &str) -> usize {
-    let mut grid = parse_grid(contents);
+fn part_2(grid: &Grid<char>) -> usize {
+    let mut grid = grid.clone();
 
     let mut free_rolls = 0;
 
@@ -80,19 +78,17 @@ fn part_2(contents: &str) -> usize {
     free_rolls
 }
 
-fn count_and_remove_rolls(grid: &mut Grid) -> Option<usize> {
+fn count_and_remove_rolls(grid: &mut Grid<char>) -> Option<usize> {
     let mut free_rolls = 0;
 
-    for r in 0..grid.len() {
-        for c in 0..grid[0].len() {
-            if grid[r][c] != '@' {
-                continue;
-            }
+    for position in grid.positions() {
+        if grid[position] != '@' {
+            continue;
+        }
 
-            if count_surrounding_rolls(grid, r, c) < 4 {
-                grid[r][c] = '.';
-                free_rolls += 1;
-            }
+        if count_surrounding_rolls(grid, position) < 4 {
+            grid[position] = '.';
+            free_rolls += 1;
         }
     }
 
@@ -103,31 +99,45 @@ fn count_and_remove_rolls(grid: &mut Grid) -> Option<usize> {
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_example_part_1() {
-        let contents = utilities::read_file_data(DAY, "example.txt");
-
-        assert_eq!(part_1(&contents), 13);
-    }
-
-    #[test]
-    fn test_input_part_1() {
-        let contents = utilities::read_file_data(DAY, "input.txt");
-
-        assert_eq!(part_1(&contents), 1372);
-    }
-
-    #[test]
-    fn test_example_part_2() {
-        let contents = utilities::read_file_data(DAY, "example.txt");
+    use utilities::rng::for_each_generated;
+
+    use std::collections::HashSet;
+
+    /// Removes every accessible roll at once, round after round, counting them per round.
+    fn remove_in_rounds(grid: &Grid<char>) -> Vec<usize> {
+        let mut rolls = grid.find_all(&'@').collect::<HashSet<_>>();
+        let mut rounds = vec![];
+
+        loop {
+            let accessible = rolls
+                .iter()
+                .filter(|roll| {
+                    grid.neighbors_8(**roll)
+                        .filter(|neighbor| rolls.contains(neighbor))
+                        .count()
+                        < 4
+                })
+                .copied()
+                .collect::<Vec<_>>();
+
+            if accessible.is_empty() {
+                return rounds;
+            }
 
-        assert_eq!(part_2(&contents), 43);
+            rounds.push(accessible.len());
+            accessible.iter().for_each(|roll| {
+                rolls.remove(roll);
+            });
+        }
     }
 
     #[test]
-    fn test_input_part_2() {
-        let contents = utilities::read_file_data(DAY, "input.txt");
+    fn test_generated_input() {
+        for_each_generated(0..20, 30, generate_grid, parse_grid, |_, grid| {
+            let rounds = remove_in_rounds(&grid);
 
-        assert_eq!(part_2(&contents), 7922);
+            assert_eq!(part_1(&grid), rounds.first().copied().unwrap_or(0));
+            assert_eq!(part_2(&grid), rounds.iter().sum());
+        });
     }
 }
@@ -1,45 +1,82 @@
 use std::ops::RangeInclusive;
 
+use utilities::{
+    Answer, Puzzle, PuzzleError,
+    parse::{integer, parse_spans, range, sections},
+    rng::Rng,
+};
+
 const DAY: usize = 5;
 
-pub fn run() {
-    utilities::run_puzzle(DAY, part_1, part_2);
-}
+pub struct Day5;
+
+impl Puzzle for Day5 {
+    type Input = Database;
+
+    fn day(&self) -> usize {
+        DAY
+    }
 
-fn part_1(contents: &str) -> usize {
-    let (ranges, ingredients) = parse_database(contents);
+    fn title(&self) -> &'static str {
+        "Cafeteria"
+    }
+
+    fn parse(&self, contents: &str) -> Result<Self::Input, PuzzleError> {
+        parse_database(contents)
+    }
+
+    fn part_1(&self, database: &Self::Input) -> Result<Answer, PuzzleError> {
+        Ok(part_1(database).to_string())
+    }
 
+    fn part_2(&self, database: &Self::Input) -> Result<Answer, PuzzleError> {
+        Ok(part_2(database).to_string())
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> Option<String> {
+        Some(generate_database(rng, size))
+    }
+}
+
+fn part_1((ranges, ingredients): &Database) -> usize {
     let ranges = merge_ranges(ranges);
 
-    count_spoiled_ingredient(&ranges, &ingredients)
+    count_spoiled_ingredient(&ranges, ingredients)
 }
 
-fn parse_database(contents: &str) -> (Vec<RangeInclusive<usize>>, Vec<usize>) {
-    let mut ranges = vec![];
-    let mut ingredients = vec![];
+type Database = (Vec<RangeInclusive<usize>>, Vec<usize>);
 
-    let mut on_ranges = true;
-    for line in contents.lines() {
-        if line.is_empty() {
-            on_ranges = false;
-            continue;
-        }
+fn parse_database(contents: &str) -> Result<Database, PuzzleError> {
+    let sections = sections(contents);
 
-        if on_ranges {
-            let range_pieces = line
-                .split("-")
-                .map(|s| s.parse::<usize>().unwrap())
-                .collect::<Vec<_>>();
+    let Some(range_lines) = sections.first() else {
+        return Err(PuzzleError::new(1, 1, "missing fresh ingredient ranges"));
+    };
+    let ingredient_lines = sections.get(1).map_or(&[][..], Vec::as_slice);
 
-            ranges.push(range_pieces[0]..=range_pieces[1]);
-        } else {
-            let ingredient = line.parse::<usize>().unwrap();
+    let ranges = parse_spans(range_lines, range(integer, "-"))?;
+    let ingredients = parse_spans(ingredient_lines, integer)?;
 
-            ingredients.push(ingredient);
-        }
-    }
+    Ok((ranges, ingredients))
+}
+
+/// `size` fresh ranges of up to a trillion IDs, often overlapping, then `size` ingredient IDs.
+fn generate_database(rng: &mut Rng, size: usize) -> String {
+    const LARGEST_ID: usize = 500_000_000_000_000;
+
+    let ranges = (0..size).map(|_| {
+        let start = rng.range(1..=LARGEST_ID);
+        let end = start + rng.range(0..=1_000_000_000_000);
+
+        format!("{start}-{end}\n")
+    });
+    let ranges = ranges.collect::<String>();
 
-    (ranges, ingredients)
+    let ingredients = (0..size)
+        .map(|_| format!("{}\n", rng.range(1..=LARGEST_ID)))
+        .collect::<String>();
+
+    format!("{ranges}\n{ingredients}")
 }
 
 fn count_spoiled_ingredient(ranges: &[RangeInclusive<usize>], ingredients: &[usize]) -> usize {
@@ -59,9 +96,7 @@ fn count_spoiled_ingredient(ranges: &[RangeInclusive<usize>], ingredients: &[usi
         .count()
 }
 
-fn part_2(contents: &str) -> usize {
-    let (ranges, _) = parse_database(contents);
-
+fn part_2((ranges, _): &Database) -> usize {
     let ranges = merge_ranges(ranges);
 
     ranges
@@ -70,8 +105,8 @@ fn part_2(contents: &str) -> usize {
         .sum()
 }
 
-fn merge_ranges(ranges: Vec<RangeInclusive<usize>>) -> Vec<RangeInclusive<usize>> {
-    let mut ranges = ranges;
+fn merge_ranges(ranges: &[RangeInclusive<usize>]) -> Vec<RangeInclusive<usize>> {
+    let mut ranges = ranges.to_vec();
     ranges.sort_by(|a, b| a.start().cmp(b.start()));
 
     let mut merged_ranges = vec![];
@@ -98,31 +133,40 @@ fn merge_ranges(ranges: Vec<RangeInclusive<usize>>) -> Vec<RangeInclusive<usize>
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_example_part_1() {
-        let contents = utilities::read_file_data(DAY, "example.txt");
-
-        assert_eq!(part_1(&contents), 3);
-    }
+    use utilities::rng::for_each_generated;
 
     #[test]
-    fn test_input_part_1() {
-        let contents = utilities::read_file_data(DAY, "input.txt");
-
-        assert_eq!(part_1(&contents), 733);
-    }
-
-    #[test]
-    fn test_example_part_2() {
-        let contents = utilities::read_file_data(DAY, "example.txt");
-
-        assert_eq!(part_2(&contents), 14);
-    }
-
-    #[test]
-    fn test_input_part_2() {
-        let contents = utilities::read_file_data(DAY, "input.txt");
-
-        assert_eq!(part_2(&contents), 345821388687084);
+    fn test_generated_input() {
+        for_each_generated(
+            0..20,
+            200,
+            generate_database,
+            parse_database,
+            |_, database| {
+                let (ranges, ingredients) = &database;
+
+                let fresh = ingredients
+                    .iter()
+                    .filter(|ingredient| ranges.iter().any(|range| range.contains(ingredient)))
+                    .count();
+
+                // Cut the IDs into pieces at every range boundary and add up the covered pieces.
+                let mut boundaries = ranges
+                    .iter()
+                    .flat_map(|range| [*range.start(), range.end() + 1])
+                    .collect::<Vec<_>>();
+                boundaries.sort();
+                boundaries.dedup();
+
+                let covered = boundaries
+                    .windows(2)
+                    .filter(|piece| ranges.iter().any(|range| range.contains(&piece[0])))
+                    .map(|piece| piece[1] - piece[0])
+                    .sum::<usize>();
+
+                assert_eq!(part_1(&database), fresh);
+                assert_eq!(part_2(&database), covered);
+            },
+        );
     }
 }
@@ -1,7 +1,37 @@
+use std::ops::Range;
+
+use utilities::{Answer, Grid, Puzzle, PuzzleError, rng::Rng};
+
 const DAY: usize = 6;
 
-pub fn run() {
-    utilities::run_puzzle(DAY, part_1, part_2);
+pub struct Day6;
+
+impl Puzzle for Day6 {
+    type Input = Vec<Problem>;
+
+    fn day(&self) -> usize {
+        DAY
+    }
+
+    fn title(&self) -> &'static str {
+        "Trash Compactor"
+    }
+
+    fn parse(&self, contents: &str) -> Result<Self::Input, PuzzleError> {
+        parse_worksheet(contents)
+    }
+
+    fn part_1(&self, problems: &Self::Input) -> Result<Answer, PuzzleError> {
+        Ok(part_1(problems).to_string())
+    }
+
+    fn part_2(&self, problems: &Self::Input) -> Result<Answer, PuzzleError> {
+        Ok(part_2(problems).to_string())
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> Option<String> {
+        Some(generate_worksheet(rng, size))
+    }
 }
 
 #[derive(Debug)]
@@ -11,152 +41,290 @@ enum Operator {
 }
 
 impl Operator {
-    fn from_str(text: &str) -> Self {
-        Self::from_char(text.chars().next().unwrap())
-    }
-
-    fn from_char(c: char) -> Self {
+    fn from_char(c: char, line: usize, column: usize) -> Result<Self, PuzzleError> {
         match c {
-            '+' => Operator::Add,
-            '*' => Operator::Multiply,
-            _ => unreachable!(),
+            '+' => Ok(Operator::Add),
+            '*' => Ok(Operator::Multiply),
+            _ => Err(PuzzleError::new(
+                line,
+                column,
+                format!("unknown operator '{c}'"),
+            )),
         }
     }
 }
 
-type Problem = (Vec<usize>, Operator);
+#[derive(Debug)]
+pub struct Problem {
+    operator: Operator,
+    /// The numbers read across each row, for part 1.
+    rows: Vec<usize>,
+    /// The numbers read down each column, right to left, for part 2.
+    columns: Vec<usize>,
+}
 
-fn part_1(contents: &str) -> usize {
-    let problems = parse_problems(contents);
+impl Problem {
+    fn solve(&self, numbers: &[usize]) -> usize {
+        match self.operator {
+            Operator::Add => numbers.iter().sum(),
+            Operator::Multiply => numbers.iter().product(),
+        }
+    }
+}
 
+fn part_1(problems: &[Problem]) -> usize {
     problems
-        .into_iter()
-        .map(|problem| solve_problem(&problem))
+        .iter()
+        .map(|problem| problem.solve(&problem.rows))
         .sum()
 }
 
-fn parse_problems(contents: &str) -> Vec<Problem> {
-    let mut lines = contents.lines().rev();
+fn part_2(problems: &[Problem]) -> usize {
+    problems
+        .iter()
+        .map(|problem| problem.solve(&problem.columns))
+        .sum()
+}
 
-    let mut problems: Vec<Problem> = lines
-        .next()
-        .unwrap()
-        .split_ascii_whitespace()
-        .map(Operator::from_str)
-        .map(|op| (vec![], op))
+/// Splits the worksheet into problems at the columns that are blank all the way down. Lines
+/// shorter than the widest one are padded with spaces, since editors often trim them.
+fn parse_worksheet(contents: &str) -> Result<Vec<Problem>, PuzzleError> {
+    let width = contents
+        .lines()
+        .map(|line| line.chars().count())
+        .max()
+        .unwrap_or(0);
+    let rows = contents
+        .lines()
+        .map(|line| {
+            let mut row = line.chars().collect::<Vec<_>>();
+            row.resize(width, ' ');
+            row
+        })
         .collect();
+    let grid = Grid::from_rows(rows)?;
 
-    for line in lines.rev() {
-        line.split_ascii_whitespace()
-            .map(|s| s.parse::<usize>().unwrap())
-            .enumerate()
-            .for_each(|(i, val)| {
-                problems[i].0.push(val);
-            });
+    if grid.height() == 0 {
+        return Err(PuzzleError::new(1, 1, "missing operator line"));
     }
 
-    problems
-}
+    let blank = |col: usize| grid.column(col).all(|c| *c == ' ');
+    let mut problems = vec![];
+    let mut start = 0;
 
-fn solve_problem(problem: &Problem) -> usize {
-    match problem.1 {
-        Operator::Add => problem.0.iter().sum(),
-        Operator::Multiply => problem.0.iter().product(),
-    }
-}
+    for col in 0..=grid.width() {
+        if col < grid.width() && !blank(col) {
+            continue;
+        }
 
-fn part_2(contents: &str) -> usize {
-    let data = parse_data(contents);
+        if start < col {
+            problems.push(parse_problem(&grid, start..col)?);
+        }
 
-    calculate_result(data)
-}
+        start = col + 1;
+    }
 
-fn parse_data(contents: &str) -> Vec<Vec<char>> {
-    contents
-        .lines()
-        .map(|line| line.chars().collect())
-        .collect()
+    Ok(problems)
 }
 
-fn calculate_result(data: Vec<Vec<char>>) -> usize {
-    let mut final_result = 0;
+/// Reads the problem in `columns`, whose last row holds its operator and every other row digits.
+fn parse_problem(grid: &Grid<char>, columns: Range<usize>) -> Result<Problem, PuzzleError> {
+    let op_row = grid.height() - 1;
+    let mut operator = None;
 
-    // Transpose the data to make it easier.
-    let num_cols = data.len();
-    let num_rows = data[0].len();
-    let op_col = num_cols - 1;
-
-    let mut op = None;
-    let mut result = None;
+    for col in columns.clone() {
+        match grid[(op_row, col)] {
+            ' ' => {}
+            c if operator.is_none() => {
+                operator = Some(Operator::from_char(c, op_row + 1, col + 1)?);
+            }
+            c => {
+                return Err(PuzzleError::new(
+                    op_row + 1,
+                    col + 1,
+                    format!("unexpected '{c}' after the operator"),
+                ));
+            }
+        }
+    }
 
-    for r in 0..num_rows {
-        if op.is_none() {
-            op = Some(Operator::from_char(data[op_col][r]));
+    let Some(operator) = operator else {
+        return Err(PuzzleError::new(
+            op_row + 1,
+            columns.start + 1,
+            "problem has no operator",
+        ));
+    };
+
+    for row in 0..op_row {
+        for col in columns.clone() {
+            let cell = grid[(row, col)];
+
+            if cell != ' ' && !cell.is_ascii_digit() {
+                return Err(PuzzleError::new(
+                    row + 1,
+                    col + 1,
+                    format!("invalid digit '{cell}'"),
+                ));
+            }
         }
+    }
+
+    let rows = (0..op_row)
+        .filter_map(|row| read_number(columns.clone().map(|col| grid[(row, col)])))
+        .collect();
+    let columns = columns
+        .rev()
+        .filter_map(|col| read_number((0..op_row).map(|row| grid[(row, col)])))
+        .collect();
 
-        let is_end = (0..num_cols).all(|c| data[c][r] == ' ');
+    Ok(Problem {
+        operator,
+        rows,
+        columns,
+    })
+}
 
-        if is_end {
-            final_result += result.unwrap();
-            result = None;
-            op = None;
+/// The number spelled by the digits among `cells`, or `None` if there aren't any.
+fn read_number(cells: impl Iterator<Item = char>) -> Option<usize> {
+    cells
+        .filter_map(|cell| cell.to_digit(10))
+        .fold(None, |number, digit| {
+            Some(number.unwrap_or(0) * 10 + digit as usize)
+        })
+}
 
-            continue;
+/// `size` problems side by side, each three or four numbers of up to four digits aligned to either
+/// side of their column, with the operator under the first column.
+fn generate_worksheet(rng: &mut Rng, size: usize) -> String {
+    let rows = rng.range(3..=4);
+    let mut lines = vec![String::new(); rows + 1];
+
+    for problem in 0..size {
+        let numbers = (0..rows)
+            .map(|_| rng.range(1..=9999).to_string())
+            .collect::<Vec<_>>();
+        let width = numbers.iter().map(String::len).max().unwrap();
+        let operator = if rng.chance(0.5) { '+' } else { '*' };
+
+        if problem > 0 {
+            lines.iter_mut().for_each(|line| line.push(' '));
         }
 
-        let current_val = data
-            .iter()
-            .take(op_col)
-            .filter(|col| col[r] != ' ')
-            .fold(0, |acc, col| {
-                acc * 10 + col[r].to_digit(10).unwrap() as usize
-            });
-
-        if let Some(r) = result {
-            match op {
-                Some(Operator::Add) => result = Some(r + current_val),
-                Some(Operator::Multiply) => result = Some(r * current_val),
-                _ => unreachable!(),
+        for (line, number) in lines.iter_mut().zip(&numbers) {
+            if rng.chance(0.5) {
+                line.push_str(&format!("{number:<width$}"));
+            } else {
+                line.push_str(&format!("{number:>width$}"));
             }
-        } else {
-            result = Some(current_val);
         }
-    }
 
-    final_result += result.unwrap();
+        lines[rows].push_str(&format!("{operator:<width$}"));
+    }
 
-    final_result
+    lines.into_iter().map(|line| line + "\n").collect()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    use utilities::rng::for_each_generated;
+
     #[test]
-    fn test_example_part_1() {
-        let contents = utilities::read_file_data(DAY, "example.txt");
+    fn test_trimmed_lines() {
+        let padded = parse_worksheet("123 328\n 45 64 \n  6 98 \n*   +  ").unwrap();
+        let trimmed = parse_worksheet("123 328\n 45 64\n  6 98\n*   +").unwrap();
 
-        assert_eq!(part_1(&contents), 4277556);
+        assert_eq!(part_1(&trimmed), part_1(&padded));
+        assert_eq!(part_2(&trimmed), part_2(&padded));
+        assert_eq!(part_1(&trimmed), 33210 + 490);
     }
 
     #[test]
-    fn test_input_part_1() {
-        let contents = utilities::read_file_data(DAY, "input.txt");
-
-        assert_eq!(part_1(&contents), 5595593539811);
+    fn test_malformed_input() {
+        assert_eq!(
+            parse_worksheet("1 2\n3 4\n+ -").unwrap_err(),
+            PuzzleError::new(3, 3, "unknown operator '-'")
+        );
+        assert_eq!(
+            parse_worksheet("1 2\n3 x\n+ *").unwrap_err(),
+            PuzzleError::new(2, 3, "invalid digit 'x'")
+        );
+        assert_eq!(
+            parse_worksheet("1 2\n3 4\n+  ").unwrap_err(),
+            PuzzleError::new(3, 3, "problem has no operator")
+        );
+        assert_eq!(
+            parse_worksheet("12\n34\n+*").unwrap_err(),
+            PuzzleError::new(3, 2, "unexpected '*' after the operator")
+        );
+        assert_eq!(
+            parse_worksheet("").unwrap_err(),
+            PuzzleError::new(1, 1, "missing operator line")
+        );
     }
 
-    #[test]
-    fn test_example_part_2() {
-        let contents = utilities::read_file_data(DAY, "example.txt");
+    /// Splits the worksheet at its blank columns into each problem's rows of text.
+    fn problem_blocks(contents: &str) -> Vec<Vec<String>> {
+        let lines = contents.lines().collect::<Vec<_>>();
+        let width = lines[0].len();
+        let blank = |col: usize| lines.iter().all(|line| line.as_bytes()[col] == b' ');
+
+        let mut blocks = vec![];
+        let mut start = 0;
+
+        for col in 0..=width {
+            if col == width || blank(col) {
+                blocks.push(
+                    lines
+                        .iter()
+                        .map(|line| line[start..col].to_string())
+                        .collect(),
+                );
+                start = col + 1;
+            }
+        }
 
-        assert_eq!(part_2(&contents), 3263827);
+        blocks
     }
 
-    #[test]
-    fn test_input_part_2() {
-        let contents = utilities::read_file_data(DAY, "input.txt");
+    fn apply(operator: &str, numbers: impl Iterator<Item = usize>) -> usize {
+        match operator {
+            "+" => numbers.sum(),
+            _ => numbers.product(),
+        }
+    }
 
-        assert_eq!(part_2(&contents), 10153315705125);
+    #[test]
+    fn test_generated_input() {
+        for_each_generated(
+            0..20,
+            50,
+            generate_worksheet,
+            parse_worksheet,
+            |contents, problems| {
+                let (mut by_rows, mut by_columns) = (0, 0);
+
+                for block in problem_blocks(contents) {
+                    let (operator, rows) = block.split_last().unwrap();
+                    let operator = operator.trim();
+
+                    by_rows += apply(operator, rows.iter().map(|row| row.trim().parse().unwrap()));
+                    by_columns += apply(
+                        operator,
+                        (0..rows[0].len()).map(|col| {
+                            rows.iter()
+                                .map(|row| row.as_bytes()[col])
+                                .filter(|c| *c != b' ')
+                                .fold(0, |number, digit| number * 10 + (digit - b'0') as usize)
+                        }),
+                    );
+                }
+
+                assert_eq!(part_1(&problems), by_rows);
+                assert_eq!(part_2(&problems), by_columns);
+            },
+        );
     }
 }
@@ -1,91 +1,204 @@
+use utilities::{Answer, Grid, Puzzle, PuzzleError, rng::Rng};
+
 const DAY: usize = 7;
 
-pub fn run() {
-    utilities::run_puzzle(DAY, part_1, part_2);
-}
+pub struct Day7;
+
+impl Puzzle for Day7 {
+    type Input = Grid<char>;
+
+    fn day(&self) -> usize {
+        DAY
+    }
 
-type Grid = Vec<Vec<char>>;
+    fn title(&self) -> &'static str {
+        "Laboratories"
+    }
 
-fn part_1(contents: &str) -> usize {
-    let grid = parse_manifold(contents);
+    fn parse(&self, contents: &str) -> Result<Self::Input, PuzzleError> {
+        parse_manifold(contents)
+    }
 
-    let (splits, _) = simulate_tachyons(&grid);
+    fn part_1(&self, grid: &Self::Input) -> Result<Answer, PuzzleError> {
+        Ok(part_1(grid).to_string())
+    }
+
+    fn part_2(&self, grid: &Self::Input) -> Result<Answer, PuzzleError> {
+        Ok(part_2(grid)?.to_string())
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> Option<String> {
+        Some(generate_manifold(rng, size))
+    }
+}
+
+fn part_1(grid: &Grid<char>) -> usize {
+    let (splits, _) = simulate_tachyons(grid);
 
     splits
 }
 
-fn parse_manifold(contents: &str) -> Grid {
-    contents
-        .lines()
-        .map(|line| line.chars().collect())
-        .collect()
+/// A splitter on either edge would send half its beam out of the manifold, so those are rejected
+/// along with a manifold that has nowhere for the beam to start.
+fn parse_manifold(contents: &str) -> Result<Grid<char>, PuzzleError> {
+    let grid = Grid::parse(contents, |c| matches!(c, '.' | 'S' | '^').then_some(c))?;
+
+    if let Some(((row, col), _)) = grid
+        .iter()
+        .find(|((_, col), c)| **c == '^' && (*col == 0 || *col == grid.width() - 1))
+    {
+        return Err(PuzzleError::new(
+            row + 1,
+            col + 1,
+            "splitter on the edge of the manifold",
+        ));
+    }
+
+    if grid.find(&'S').is_none() {
+        return Err(PuzzleError::new(1, 1, "missing start 'S'"));
+    }
+
+    Ok(grid)
 }
 
-fn part_2(contents: &str) -> usize {
-    let grid = parse_manifold(contents);
+/// A manifold `2 * size + 1` wide with `size` rows of splitters below the start, every other row
+/// left empty. A quarter of the inner cells hold a splitter, never two side by side. From a size
+/// of a few hundred, part 2 has too many timelines to count.
+fn generate_manifold(rng: &mut Rng, size: usize) -> String {
+    let width = 2 * size + 1;
+
+    let mut lines = vec![format!("{}S{}", ".".repeat(size), ".".repeat(size))];
 
-    let (_, beam_count) = simulate_tachyons(&grid);
+    for _ in 0..size {
+        let mut row = vec!['.'; width];
+
+        for col in 1..width - 1 {
+            if row[col - 1] != '^' && rng.chance(0.25) {
+                row[col] = '^';
+            }
+        }
+
+        lines.push(".".repeat(width));
+        lines.push(row.into_iter().collect());
+    }
+
+    lines.into_iter().map(|line| line + "\n").collect()
+}
+
+fn part_2(grid: &Grid<char>) -> Result<usize, PuzzleError> {
+    let (_, beam_count) = simulate_tachyons(grid);
 
     beam_count
 }
 
-fn simulate_tachyons(grid: &Grid) -> (usize, usize) {
-    let mut beams = vec![0; grid[0].len()];
+/// The number of splitters the beam reaches, and the number of timelines. The timelines double at
+/// most splitters, so big manifolds can have more than a `usize` holds. The beam counts then stop
+/// at `usize::MAX`, which still tells the splits apart, and the error points at the first splitter
+/// that overflowed.
+fn simulate_tachyons(grid: &Grid<char>) -> (usize, Result<usize, PuzzleError>) {
+    let mut beams = vec![0usize; grid.width()];
+    let mut overflow = None;
 
     let mut splits = 0;
-    for row_data in grid.iter() {
+    for (row, row_data) in grid.rows().enumerate() {
         for (col, c) in row_data.iter().enumerate() {
             if c == &'S' {
                 beams[col] = 1;
             } else if c == &'^' && beams[col] != 0 {
-                beams[col - 1] += beams[col];
-                beams[col + 1] += beams[col];
-                beams[col] = 0;
+                let beam = std::mem::take(&mut beams[col]);
+
+                for side in [col - 1, col + 1] {
+                    beams[side] = beams[side].checked_add(beam).unwrap_or_else(|| {
+                        overflow.get_or_insert((row + 1, col + 1));
+                        usize::MAX
+                    });
+                }
 
                 splits += 1;
             }
         }
     }
 
-    (splits, beams.into_iter().sum())
+    let too_many = |(line, column)| PuzzleError::new(line, column, "too many timelines to count");
+    let timelines = match overflow {
+        Some(position) => Err(too_many(position)),
+        None => beams
+            .into_iter()
+            .try_fold(0usize, usize::checked_add)
+            .ok_or_else(|| too_many((grid.height(), 1))),
+    };
+
+    (splits, timelines)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_example_part_1() {
-        let contents = utilities::read_file_data(DAY, "example.txt");
+    use utilities::rng::for_each_generated;
 
-        assert_eq!(part_1(&contents), 21);
-    }
+    use std::collections::HashMap;
 
     #[test]
-    fn test_input_part_1() {
-        let contents = utilities::read_file_data(DAY, "input.txt");
-
-        assert_eq!(part_1(&contents), 1662);
+    fn test_malformed_input() {
+        assert_eq!(
+            parse_manifold("S.\n^.").unwrap_err(),
+            PuzzleError::new(2, 1, "splitter on the edge of the manifold")
+        );
+        assert_eq!(
+            parse_manifold(".S.\n..^").unwrap_err(),
+            PuzzleError::new(2, 3, "splitter on the edge of the manifold")
+        );
+        assert_eq!(
+            parse_manifold("...\n.^.").unwrap_err(),
+            PuzzleError::new(1, 1, "missing start 'S'")
+        );
     }
 
-    #[test]
-    fn test_example_part_2() {
-        let contents = utilities::read_file_data(DAY, "example.txt");
+    /// Follows the beam from the start row by row, returning the splitters it reaches and the
+    /// number of timelines: one per way through the manifold.
+    fn follow_beams(grid: &Grid<char>) -> (usize, usize) {
+        let start = grid.find(&'S').unwrap();
+        let mut timelines = HashMap::from([(start.1, 1)]);
+        let mut splits = 0;
+
+        for row in grid.rows().skip(start.0 + 1) {
+            let mut next = HashMap::new();
+
+            for (col, count) in timelines {
+                if row[col] == '^' {
+                    splits += 1;
+                    *next.entry(col - 1).or_default() += count;
+                    *next.entry(col + 1).or_default() += count;
+                } else {
+                    *next.entry(col).or_default() += count;
+                }
+            }
+
+            timelines = next;
+        }
 
-        assert_eq!(part_2(&contents), 40);
+        (splits, timelines.values().sum())
     }
 
     #[test]
-    fn test_example2_part_2() {
-        let contents = utilities::read_file_data(DAY, "example2.txt");
+    fn test_generated_input() {
+        for_each_generated(0..20, 40, generate_manifold, parse_manifold, |_, grid| {
+            let (splits, timelines) = follow_beams(&grid);
 
-        assert_eq!(part_2(&contents), 10);
+            assert_eq!(part_1(&grid), splits);
+            assert_eq!(part_2(&grid), Ok(timelines));
+        });
     }
 
     #[test]
-    fn test_input_part_2() {
-        let contents = utilities::read_file_data(DAY, "input.txt");
-
-        assert_eq!(part_2(&contents), 40941112789504);
+    fn test_too_many_timelines() {
+        let grid = parse_manifold(&generate_manifold(&mut Rng::new(1), 300)).unwrap();
+
+        assert!(part_1(&grid) > 0);
+        assert_eq!(
+            part_2(&grid).unwrap_err().message,
+            "too many timelines to count"
+        );
     }
 }
@@ -1,198 +1,216 @@
-use std::{cmp::Reverse, collections::HashSet};
+use std::collections::HashSet;
+
+use utilities::{
+    Answer, Puzzle, PuzzleError,
+    kd_tree::KdTree,
+    parse::{integer, map, parse_lines, separated_array},
+    rng::Rng,
+    union_find::UnionFind,
+};
 
 const DAY: usize = 8;
 
-pub fn run() {
-    utilities::run_puzzle(DAY, part_1, part_2);
+pub struct Day8;
+
+impl Puzzle for Day8 {
+    type Input = Vec<Point>;
+
+    fn day(&self) -> usize {
+        DAY
+    }
+
+    fn title(&self) -> &'static str {
+        "Playground"
+    }
+
+    fn parse(&self, contents: &str) -> Result<Self::Input, PuzzleError> {
+        parse_junction_boxes(contents)
+    }
+
+    fn part_1(&self, junction_boxes: &Self::Input) -> Result<Answer, PuzzleError> {
+        Ok(part_1(junction_boxes).to_string())
+    }
+
+    fn part_2(&self, junction_boxes: &Self::Input) -> Result<Answer, PuzzleError> {
+        Ok(part_2(junction_boxes)?.to_string())
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> Option<String> {
+        Some(generate_junction_boxes(rng, size))
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-struct Point {
+pub struct Point {
     x: isize,
     y: isize,
     z: isize,
 }
 
 impl Point {
-    fn new(text: &str) -> Self {
-        let pieces = text
-            .split(",")
-            .map(|s| s.parse::<isize>().unwrap())
-            .collect::<Vec<_>>();
-
-        Self {
-            x: pieces[0],
-            y: pieces[1],
-            z: pieces[2],
-        }
+    fn new([x, y, z]: [isize; 3]) -> Self {
+        Self { x, y, z }
     }
 
-    fn distance_sq(&self, other: &Point) -> isize {
-        (self.x - other.x).pow(2) + (self.y - other.y).pow(2) + (self.z - other.z).pow(2)
+    fn coordinates(&self) -> [i64; 3] {
+        [self.x, self.y, self.z].map(|coordinate| coordinate as i64)
     }
 }
 
-fn part_1(contents: &str) -> usize {
-    let junction_boxes = parse_junction_boxes(contents);
+fn kd_tree(junction_boxes: &[Point]) -> KdTree<3> {
+    KdTree::new(
+        &junction_boxes
+            .iter()
+            .map(Point::coordinates)
+            .collect::<Vec<_>>(),
+    )
+}
 
+fn part_1(junction_boxes: &[Point]) -> usize {
     join_junction_boxes(junction_boxes, 1000)
 }
 
-fn parse_junction_boxes(contents: &str) -> Vec<Point> {
-    contents.lines().map(Point::new).collect()
+fn parse_junction_boxes(contents: &str) -> Result<Vec<Point>, PuzzleError> {
+    parse_lines(contents, map(separated_array(integer, ","), Point::new))
 }
 
-fn join_junction_boxes(junction_boxes: Vec<Point>, num_to_join: usize) -> usize {
-    let distances = sort_by_distance(&junction_boxes);
+/// `size` distinct junction boxes (at least two) with coordinates below 100,000.
+fn generate_junction_boxes(rng: &mut Rng, size: usize) -> String {
+    let mut seen = HashSet::new();
 
-    let mut circuits: Vec<HashSet<Point>> = vec![];
-
-    for (_, a, b) in distances.iter().take(num_to_join) {
-        let a_pos = circuits.iter().position(|circuit| circuit.contains(a));
-        let b_pos = circuits.iter().position(|circuit| circuit.contains(b));
-
-        match (a_pos, b_pos) {
-            // Neither seen, join into new circuit.
-            (None, None) => {
-                let mut circuit = HashSet::new();
-                circuit.insert(*a);
-                circuit.insert(*b);
-                circuits.push(circuit)
-            }
-            // One is seen, add other to seen set.
-            (Some(i), None) => {
-                circuits[i].insert(*b);
-            }
-            (None, Some(i)) => {
-                circuits[i].insert(*a);
-            }
-            // Both are in different groups, need to join.
-            (Some(i), Some(j)) if i != j => {
-                if i < j {
-                    let other_circuit = circuits.remove(j);
-                    circuits[i].extend(other_circuit);
-                } else {
-                    let other_circuit = circuits.remove(i);
-                    circuits[j].extend(other_circuit);
-                }
-            }
-            _ => {}
-        }
+    while seen.len() < size.max(2) {
+        seen.insert([(); 3].map(|_| rng.range(0..=99_999)));
     }
 
-    circuits.sort_by_key(|circuit| Reverse(circuit.len()));
+    let mut boxes = seen.into_iter().collect::<Vec<_>>();
+    boxes.sort();
+    rng.shuffle(&mut boxes);
 
-    circuits
-        .iter()
-        .take(3)
-        .map(|circuit| circuit.len())
-        .product()
+    boxes
+        .into_iter()
+        .map(|[x, y, z]| format!("{x},{y},{z}\n"))
+        .collect()
 }
 
-fn sort_by_distance(junction_boxes: &[Point]) -> Vec<(isize, Point, Point)> {
-    let mut distances = vec![];
-
-    for i in 0..junction_boxes.len() {
-        for j in (i + 1)..junction_boxes.len() {
-            let a = junction_boxes[i];
-            let b = junction_boxes[j];
+/// Kruskal's algorithm for the first `num_to_join` closest pairs: the sizes of the three largest
+/// circuits multiplied together.
+fn join_junction_boxes(junction_boxes: &[Point], num_to_join: usize) -> usize {
+    let mut circuits = UnionFind::new(junction_boxes.len());
 
-            distances.push((a.distance_sq(&b), a, b));
-        }
+    for (_, a, b) in kd_tree(junction_boxes).k_closest_pairs(num_to_join) {
+        circuits.union(a, b);
     }
 
-    distances.sort_by(|(a, ..), (b, ..)| a.cmp(b));
-
-    distances
+    circuits.component_sizes().iter().take(3).product()
 }
 
-fn part_2(contents: &str) -> usize {
-    let junction_boxes = parse_junction_boxes(contents);
-
-    join_junction_boxes_v2(junction_boxes)
+fn part_2(junction_boxes: &[Point]) -> Result<usize, PuzzleError> {
+    join_all_junction_boxes(junction_boxes)
 }
 
-fn join_junction_boxes_v2(junction_boxes: Vec<Point>) -> usize {
-    let distances = sort_by_distance(&junction_boxes);
-    let mut join_score = None;
-
-    let mut circuits: Vec<HashSet<Point>> = vec![];
-
-    for (_, a, b) in distances.iter() {
-        let a_pos = circuits.iter().position(|circuit| circuit.contains(a));
-        let b_pos = circuits.iter().position(|circuit| circuit.contains(b));
-
-        match (a_pos, b_pos) {
-            // Neither seen, join into new circuit.
-            (None, None) => {
-                let mut circuit = HashSet::new();
-                circuit.insert(*a);
-                circuit.insert(*b);
-                circuits.push(circuit)
-            }
-            // One is seen, add other to seen set.
-            (Some(i), None) => {
-                circuits[i].insert(*b);
-            }
-            (None, Some(i)) => {
-                circuits[i].insert(*a);
-            }
-            // Both are in different groups, need to join.
-            (Some(i), Some(j)) if i != j => {
-                if i < j {
-                    let other_circuit = circuits.remove(j);
-                    circuits[i].extend(other_circuit);
-                } else {
-                    let other_circuit = circuits.remove(i);
-                    circuits[j].extend(other_circuit);
-                }
-            }
-            _ => {}
-        }
+/// Kruskal's algorithm until every junction box is in one circuit: the X coordinates of the last
+/// pair it took to get there multiplied together. With fewer than two boxes there's no such pair,
+/// so the error points at the line where the second box should be.
+fn join_all_junction_boxes(junction_boxes: &[Point]) -> Result<usize, PuzzleError> {
+    let mut circuits = UnionFind::new(junction_boxes.len());
 
-        if circuits
-            .first()
-            .is_some_and(|circuit| circuit.len() == junction_boxes.len())
-        {
-            join_score = Some(a.x * b.x);
-            break;
+    for (_, a, b) in kd_tree(junction_boxes).closest_pairs() {
+        if circuits.union(a, b) && circuits.components() == 1 {
+            return Ok((junction_boxes[a].x * junction_boxes[b].x) as usize);
         }
     }
 
-    join_score.unwrap() as usize
+    Err(PuzzleError::new(
+        junction_boxes.len() + 1,
+        1,
+        "fewer than two junction boxes to join",
+    ))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    use utilities::rng::for_each_generated;
+
+    use utilities::kd_tree::distance_sq;
+
     #[test]
     fn test_example_part_1() {
         let contents = utilities::read_file_data(DAY, "example.txt");
 
-        let junction_boxes = parse_junction_boxes(&contents);
+        let junction_boxes = parse_junction_boxes(&contents).unwrap();
 
-        assert_eq!(join_junction_boxes(junction_boxes, 10), 40);
+        assert_eq!(join_junction_boxes(&junction_boxes, 10), 40);
     }
 
     #[test]
-    fn test_input_part_1() {
-        let contents = utilities::read_file_data(DAY, "input.txt");
-
-        assert_eq!(part_1(&contents), 79560);
+    fn test_too_few_junction_boxes() {
+        let junction_boxes = parse_junction_boxes("162,817,812\n").unwrap();
+
+        assert_eq!(
+            part_2(&junction_boxes),
+            Err(PuzzleError::new(
+                2,
+                1,
+                "fewer than two junction boxes to join"
+            ))
+        );
+        assert_eq!(
+            part_2(&[]),
+            Err(PuzzleError::new(
+                1,
+                1,
+                "fewer than two junction boxes to join"
+            ))
+        );
     }
 
-    #[test]
-    fn test_example_part_2() {
-        let contents = utilities::read_file_data(DAY, "example.txt");
+    /// Every pair of junction boxes as `(distance², a, b)`, closest first, measured one by one.
+    fn all_pairs(junction_boxes: &[Point]) -> Vec<(i64, usize, usize)> {
+        let mut pairs = vec![];
+
+        for a in 0..junction_boxes.len() {
+            for b in (a + 1)..junction_boxes.len() {
+                let distance = distance_sq(
+                    &junction_boxes[a].coordinates(),
+                    &junction_boxes[b].coordinates(),
+                );
 
-        assert_eq!(part_2(&contents), 25272);
+                pairs.push((distance, a, b));
+            }
+        }
+
+        pairs.sort();
+
+        pairs
     }
 
     #[test]
-    fn test_input_part_2() {
-        let contents = utilities::read_file_data(DAY, "input.txt");
-
-        assert_eq!(part_2(&contents), 31182420);
+    fn test_generated_input() {
+        for_each_generated(
+            0..10,
+            60,
+            generate_junction_boxes,
+            parse_junction_boxes,
+            |_, junction_boxes| {
+                let pairs = all_pairs(&junction_boxes);
+                let mut circuits = UnionFind::new(junction_boxes.len());
+
+                for (_, a, b) in &pairs[..10] {
+                    circuits.union(*a, *b);
+                }
+                let largest = circuits.component_sizes().iter().take(3).product();
+
+                let (_, a, b) = pairs
+                    .iter()
+                    .find(|(_, a, b)| circuits.union(*a, *b) && circuits.components() == 1)
+                    .unwrap();
+                let last_pair = (junction_boxes[*a].x * junction_boxes[*b].x) as usize;
+
+                assert_eq!(join_junction_boxes(&junction_boxes, 10), largest);
+                assert_eq!(part_2(&junction_boxes), Ok(last_pair));
+            },
+        );
     }
 }
@@ -1,26 +1,50 @@
+use utilities::{
+    Answer, Grid, Puzzle, PuzzleError,
+    parse::{integer, map, parse_lines, separated_array},
+    rng::Rng,
+};
+
 const DAY: usize = 9;
 
-pub fn run() {
-    utilities::run_puzzle(DAY, part_1, part_2);
+pub struct Day9;
+
+impl Puzzle for Day9 {
+    type Input = Vec<Tile>;
+
+    fn day(&self) -> usize {
+        DAY
+    }
+
+    fn title(&self) -> &'static str {
+        "Movie Theater"
+    }
+
+    fn parse(&self, contents: &str) -> Result<Self::Input, PuzzleError> {
+        parse_tiles(contents)
+    }
+
+    fn part_1(&self, tiles: &Self::Input) -> Result<Answer, PuzzleError> {
+        Ok(part_1(tiles)?.to_string())
+    }
+
+    fn part_2(&self, tiles: &Self::Input) -> Result<Answer, PuzzleError> {
+        Ok(part_2(tiles)?.to_string())
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> Option<String> {
+        Some(generate_tiles(rng, size))
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
-struct Tile {
+pub struct Tile {
     row: usize,
     col: usize,
 }
 
 impl Tile {
-    fn new(text: &str) -> Self {
-        let pieces = text
-            .split(",")
-            .map(|s| s.parse::<usize>().unwrap())
-            .collect::<Vec<_>>();
-
-        Self {
-            row: pieces[0],
-            col: pieces[1],
-        }
+    fn new([row, col]: [usize; 2]) -> Self {
+        Self { row, col }
     }
 
     fn area(&self, other: &Tile) -> usize {
@@ -28,17 +52,77 @@ impl Tile {
     }
 }
 
-fn part_1(contents: &str) -> usize {
-    let tiles = parse_tiles(contents);
-
+fn part_1(tiles: &[Tile]) -> Result<usize, PuzzleError> {
     find_largest_area(tiles)
 }
 
-fn parse_tiles(contents: &str) -> Vec<Tile> {
-    contents.lines().map(Tile::new).collect()
+fn parse_tiles(contents: &str) -> Result<Vec<Tile>, PuzzleError> {
+    parse_lines(contents, map(separated_array(integer, ","), Tile::new))
 }
 
-fn find_largest_area(tiles: Vec<Tile>) -> usize {
+/// A loop of red tiles around `size` bands stacked on top of each other, each band a run of
+/// columns that overlaps the run above it. Walking the loop, each tile shares a row or column with
+/// the one before. Rows and columns are swapped half the time, so either side can be the ragged one.
+fn generate_tiles(rng: &mut Rng, size: usize) -> String {
+    const STEP: usize = 1000;
+
+    let mut rows = vec![rng.range(0..=STEP)];
+    let mut bands: Vec<(usize, usize)> = vec![];
+
+    for _ in 0..size.max(1) {
+        rows.push(rows.last().unwrap() + rng.range(1..=STEP));
+
+        let (left, right) = match bands.last() {
+            None => {
+                let left = rng.range(0..=STEP * 10);
+                (left, left + rng.range(1..=STEP * 10))
+            }
+            Some(&(above_left, above_right)) => {
+                let left = rng.range(above_left.saturating_sub(STEP * 5)..=above_right - 1);
+                (left, left.max(above_left) + rng.range(1..=STEP * 5))
+            }
+        };
+
+        bands.push((left, right));
+    }
+
+    // Down the right-hand edges, then back up the left-hand ones.
+    let mut tiles = vec![(rows[0], bands[0].1)];
+    for (i, pair) in bands.windows(2).enumerate() {
+        if pair[0].1 != pair[1].1 {
+            tiles.extend([(rows[i + 1], pair[0].1), (rows[i + 1], pair[1].1)]);
+        }
+    }
+
+    let bottom = *rows.last().unwrap();
+    tiles.extend([
+        (bottom, bands.last().unwrap().1),
+        (bottom, bands.last().unwrap().0),
+    ]);
+
+    for (i, pair) in bands.windows(2).enumerate().rev() {
+        if pair[0].0 != pair[1].0 {
+            tiles.extend([(rows[i + 1], pair[1].0), (rows[i + 1], pair[0].0)]);
+        }
+    }
+    tiles.push((rows[0], bands[0].0));
+
+    let transpose = rng.chance(0.5);
+
+    tiles
+        .into_iter()
+        .map(|(row, col)| match transpose {
+            true => format!("{col},{row}\n"),
+            false => format!("{row},{col}\n"),
+        })
+        .collect()
+}
+
+fn no_red_tiles() -> PuzzleError {
+    PuzzleError::new(1, 1, "no red tiles")
+}
+
+fn find_largest_area(tiles: &[Tile]) -> Result<usize, PuzzleError> {
     tiles
         .iter()
         .enumerate()
@@ -51,42 +135,222 @@ fn find_largest_area(tiles: Vec<Tile>) -> usize {
                 .unwrap()
         })
         .max()
-        .unwrap()
+        .ok_or_else(no_red_tiles)
+}
+
+fn part_2(tiles: &[Tile]) -> Result<usize, PuzzleError> {
+    find_largest_area_inside(tiles)
+}
+
+/// The tiles' rows and columns squeezed down to their rank among the distinct values, with a gap
+/// between each pair of neighbours: value `i` lands on line `2 * i` and everything strictly
+/// between values `i` and `i + 1` on line `2 * i + 1`. The polygon keeps its shape, but the grid is
+/// only as big as the number of tiles.
+struct CompressedFloor {
+    rows: Vec<usize>,
+    cols: Vec<usize>,
+    /// How many cells outside the polygon there are above and to the left of each cell, with an
+    /// extra row and column of zeros first.
+    outside_before: Grid<usize>,
+}
+
+impl CompressedFloor {
+    fn new(tiles: &[Tile]) -> Self {
+        let distinct = |coordinate: fn(&Tile) -> usize| {
+            let mut values = tiles.iter().map(coordinate).collect::<Vec<_>>();
+            values.sort();
+            values.dedup();
+            values
+        };
+
+        let rows = distinct(|tile| tile.row);
+        let cols = distinct(|tile| tile.col);
+
+        let mut floor = Self {
+            outside_before: Grid::new(0, 0, 0),
+            rows,
+            cols,
+        };
+
+        let inside = floor.fill(tiles);
+
+        let mut outside_before = Grid::new(inside.width() + 1, inside.height() + 1, 0);
+        for ((row, col), inside) in inside.iter() {
+            outside_before[(row + 1, col + 1)] = usize::from(!inside)
+                + outside_before[(row, col + 1)]
+                + outside_before[(row + 1, col)]
+                - outside_before[(row, col)];
+        }
+        floor.outside_before = outside_before;
+
+        floor
+    }
+
+    fn position(&self, tile: &Tile) -> (usize, usize) {
+        let line = |values: &[usize], value: usize| 2 * values.binary_search(&value).unwrap();
+
+        (line(&self.rows, tile.row), line(&self.cols, tile.col))
+    }
+
+    /// Marks the cells on the polygon's edges, and the ones inside it by counting the vertical
+    /// edges to their left. An edge counts if it covers the cell's row or starts on it, which
+    /// reads a cell on a tile row as if it were nudged just below it, so rays running along
+    /// horizontal edges aren't miscounted.
+    fn fill(&self, tiles: &[Tile]) -> Grid<bool> {
+        let mut inside = Grid::new(2 * self.cols.len() - 1, 2 * self.rows.len() - 1, false);
+        let mut vertical_edges = vec![];
+
+        for (i, tile) in tiles.iter().enumerate() {
+            let (row, col) = self.position(tile);
+            let (next_row, next_col) = self.position(&tiles[(i + 1) % tiles.len()]);
+
+            for edge_row in row.min(next_row)..=row.max(next_row) {
+                for edge_col in col.min(next_col)..=col.max(next_col) {
+                    inside[(edge_row, edge_col)] = true;
+                }
+            }
+
+            if col == next_col && row != next_row {
+                vertical_edges.push((row.min(next_row)..row.max(next_row), col));
+            }
+        }
+
+        for row in 0..inside.height() {
+            let mut crossings = vertical_edges
+                .iter()
+                .filter(|(rows, _)| rows.contains(&row))
+                .map(|(_, col)| *col)
+                .collect::<Vec<_>>();
+            crossings.sort();
+
+            for pair in crossings.chunks_exact(2) {
+                for col in pair[0]..=pair[1] {
+                    inside[(row, col)] = true;
+                }
+            }
+        }
+
+        inside
+    }
+
+    /// Whether the rectangle with `first` and `second` at opposite corners has no cell outside
+    /// the polygon.
+    fn is_inside(&self, first: &Tile, second: &Tile) -> bool {
+        let (first_row, first_col) = self.position(first);
+        let (second_row, second_col) = self.position(second);
+
+        let (top, bottom) = (first_row.min(second_row), first_row.max(second_row) + 1);
+        let (left, right) = (first_col.min(second_col), first_col.max(second_col) + 1);
+
+        let outside = &self.outside_before;
+        outside[(bottom, right)] + outside[(top, left)]
+            == outside[(top, right)] + outside[(bottom, left)]
+    }
 }
 
-fn part_2(contents: &str) -> usize {
-    0
+fn find_largest_area_inside(tiles: &[Tile]) -> Result<usize, PuzzleError> {
+    if tiles.is_empty() {
+        return Err(no_red_tiles());
+    }
+
+    let floor = CompressedFloor::new(tiles);
+
+    tiles
+        .iter()
+        .enumerate()
+        .flat_map(|(i, first)| tiles.iter().skip(i).map(move |second| (first, second)))
+        .filter(|(first, second)| floor.is_inside(first, second))
+        .map(|(first, second)| first.area(second))
+        .max()
+        .ok_or_else(no_red_tiles)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    use utilities::rng::for_each_generated;
+
     #[test]
-    fn test_example_part_1() {
-        let contents = utilities::read_file_data(DAY, "example.txt");
+    fn test_no_red_tiles() {
+        let tiles = parse_tiles("").unwrap();
 
-        assert_eq!(part_1(&contents), 50);
+        assert_eq!(part_1(&tiles), Err(PuzzleError::new(1, 1, "no red tiles")));
+        assert_eq!(part_2(&tiles), Err(PuzzleError::new(1, 1, "no red tiles")));
     }
 
     #[test]
-    fn test_input_part_1() {
-        let contents = utilities::read_file_data(DAY, "input.txt");
+    fn test_generated_input() {
+        for_each_generated(0..20, 30, generate_tiles, parse_tiles, |contents, tiles| {
+            for (i, tile) in tiles.iter().enumerate() {
+                let next = tiles[(i + 1) % tiles.len()];
+
+                assert!(
+                    (tile.row == next.row) != (tile.col == next.col),
+                    "{contents}"
+                );
+            }
 
-        assert_eq!(part_1(&contents), 4715966250);
+            let rows = tiles.iter().map(|tile| tile.row);
+            let cols = tiles.iter().map(|tile| tile.col);
+            let bounds = (rows.clone().max().unwrap() - rows.min().unwrap() + 1)
+                * (cols.clone().max().unwrap() - cols.min().unwrap() + 1);
+
+            assert!(part_1(&tiles).unwrap() <= bounds);
+            assert!(part_2(&tiles).unwrap() <= part_1(&tiles).unwrap());
+        });
     }
 
-    #[test]
-    fn test_example_part_2() {
-        let contents = utilities::read_file_data(DAY, "example.txt");
+    /// Whether `(row, col)` is on the loop of tiles or inside it, by casting a ray to the left
+    /// from just below the point.
+    fn point_inside(tiles: &[Tile], row: usize, col: usize) -> bool {
+        let mut crossings = 0;
+
+        for (i, tile) in tiles.iter().enumerate() {
+            let next = tiles[(i + 1) % tiles.len()];
+            let (top, bottom) = (tile.row.min(next.row), tile.row.max(next.row));
+            let (left, right) = (tile.col.min(next.col), tile.col.max(next.col));
+
+            if (top..=bottom).contains(&row) && (left..=right).contains(&col) {
+                return true;
+            }
+
+            if tile.col == next.col && tile.col < col && (top..bottom).contains(&row) {
+                crossings += 1;
+            }
+        }
 
-        assert_eq!(part_2(&contents), 0);
+        crossings % 2 == 1
     }
 
     #[test]
-    fn test_input_part_2() {
-        let contents = utilities::read_file_data(DAY, "input.txt");
+    fn test_generated_input_part_2() {
+        for_each_generated(0..20, 6, generate_tiles, parse_tiles, |contents, tiles| {
+            // Keep the shape but shrink the coordinates, so every point can be checked.
+            let rank = |values: Vec<usize>, value: usize| {
+                2 * values.iter().filter(|other| **other < value).count()
+            };
+            let rows = tiles.iter().map(|tile| tile.row).collect::<Vec<_>>();
+            let cols = tiles.iter().map(|tile| tile.col).collect::<Vec<_>>();
+            let tiles = tiles
+                .iter()
+                .map(|tile| Tile::new([rank(rows.clone(), tile.row), rank(cols.clone(), tile.col)]))
+                .collect::<Vec<_>>();
+
+            let largest = tiles
+                .iter()
+                .flat_map(|first| tiles.iter().map(move |second| (first, second)))
+                .filter(|(first, second)| {
+                    (first.row.min(second.row)..=first.row.max(second.row)).all(|row| {
+                        (first.col.min(second.col)..=first.col.max(second.col))
+                            .all(|col| point_inside(&tiles, row, col))
+                    })
+                })
+                .map(|(first, second)| first.area(second))
+                .max()
+                .unwrap();
 
-        assert_eq!(part_2(&contents), 0);
+            assert_eq!(part_2(&tiles), Ok(largest), "{contents}");
+        });
     }
 }
@@ -1,27 +1,522 @@
-mod day1;
-mod day10;
-mod day11;
-mod day2;
-mod day3;
-mod day4;
-mod day5;
-mod day6;
-mod day7;
-mod day8;
-mod day9;
+mod cli;
+mod scaffold;
+
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use cli::{Args, BenchArgs, Command, FetchArgs, GenerateArgs, SubmitArgs};
+use utilities::{
+    Solution,
+    bench::{Baseline, bench_puzzle},
+    fetch::{self, Client, InputFetcher},
+    inputs,
+    journal::Journal,
+    manifest::{MANIFEST_FILE, Verdict},
+    profiles,
+    report::ReportWriter,
+    rng::Rng,
+    submit::{self, GuessLog, Outcome},
+};
+
+const JOURNAL_FILE: &str = "journal.toml";
+const NOTES_FILE: &str = "notes.txt";
+
+macro_rules! register_days {
+    ($($module:ident::$solution:ident),* $(,)?) => {
+        $(mod $module;)*
+
+        /// Every implemented day, in day order.
+        static SOLUTIONS: &[&dyn Solution] = &[$(&$module::$solution),*];
+    };
+}
+
+register_days! {
+    day1::Day1,
+    day2::Day2,
+    day3::Day3,
+    day4::Day4,
+    day5::Day5,
+    day6::Day6,
+    day7::Day7,
+    day8::Day8,
+    day9::Day9,
+    day10::Day10,
+    day11::Day11,
+}
+
+fn find_solution(day: usize) -> Option<&'static dyn Solution> {
+    SOLUTIONS
+        .iter()
+        .find(|solution| solution.day() == day)
+        .copied()
+}
 
 fn main() {
-    println!("Advent of code 2025");
-
-    day1::run();
-    day2::run();
-    day3::run();
-    day4::run();
-    day5::run();
-    day6::run();
-    day7::run();
-    day8::run();
-    day9::run();
-    day10::run();
-    day11::run();
+    let available_days = SOLUTIONS
+        .iter()
+        .map(|solution| solution.day())
+        .collect::<Vec<_>>();
+
+    let args = match cli::parse_args(std::env::args().skip(1), &available_days) {
+        Ok(Some(args)) => args,
+        Ok(None) => {
+            println!("{}", cli::USAGE);
+            return;
+        }
+        Err(message) => {
+            eprintln!("{message}\n\n{}", cli::USAGE);
+            std::process::exit(2);
+        }
+    };
+
+    let input_root =
+        inputs::resolve(&args.inputs, env_var, &current_dir()).unwrap_or_else(|error| {
+            eprintln!("{error}");
+            std::process::exit(2);
+        });
+    inputs::set_root(input_root);
+
+    // Generated inputs go to stdout on their own, so they can be piped into another run.
+    if !matches!(args.command, Command::Generate(_)) {
+        println!("Advent of code 2025");
+    }
+
+    let solutions = args
+        .days
+        .iter()
+        .filter_map(|day| find_solution(*day))
+        .collect::<Vec<_>>();
+
+    match &args.command {
+        Command::Run if args.profiles => run_profiles(&solutions, &args),
+        Command::Run => run_puzzles(&solutions, &args),
+        Command::Bench(bench_args) => bench_puzzles(&solutions, &args, bench_args),
+        Command::Fetch(fetch_args) => fetch_inputs(&args.days, fetch_args),
+        Command::Submit(submit_args) => submit_answer(args.days[0], &args, submit_args),
+        Command::Generate(generate_args) => generate_input(solutions[0], generate_args),
+        Command::New => new_day(args.days[0]),
+        Command::Start => start_day(args.days[0]),
+        Command::Solve { note } => solve_part(solutions[0], &args, note.clone()),
+        Command::Notes => save_journal(&load_journal()),
+    }
+}
+
+/// Reads an environment variable, falling back to this crate's directory at build time for
+/// `CARGO_MANIFEST_DIR`, so inputs are found when the binary runs from anywhere.
+fn env_var(name: &str) -> Option<String> {
+    std::env::var(name)
+        .ok()
+        .or_else(|| (name == "CARGO_MANIFEST_DIR").then(|| env!("CARGO_MANIFEST_DIR").to_string()))
+}
+
+fn current_dir() -> PathBuf {
+    std::env::current_dir().unwrap_or_default()
+}
+
+fn run_puzzles(solutions: &[&dyn Solution], args: &Args) {
+    let mut report = match &args.report {
+        Some((format, path)) => match ReportWriter::create(*format, path) {
+            Ok(writer) => Some(writer),
+            Err(error) => {
+                eprintln!("Unable to create report {}: {error}", path.display());
+                std::process::exit(1);
+            }
+        },
+        None => None,
+    };
+
+    let started = Instant::now();
+    let records = utilities::run_puzzles(solutions, &args.options, args.jobs);
+    let wall_clock = started.elapsed();
+
+    // Parsing is shared by both parts of a day, so count it once per day.
+    let task_time = records
+        .chunk_by(|a, b| a.day == b.day)
+        .map(|day| {
+            day[0].parse_duration + day.iter().map(|record| record.duration).sum::<Duration>()
+        })
+        .sum::<Duration>();
+
+    println!(
+        "Total: {wall_clock:?} wall clock, {task_time:?} across tasks ({} job(s))",
+        args.jobs
+    );
+
+    let wrong_answers = records
+        .iter()
+        .filter(|record| matches!(record.verdict, Verdict::Wrong { .. }))
+        .count();
+
+    if let Some(writer) = &mut report {
+        let written = records
+            .iter()
+            .try_for_each(|record| writer.write_record(record))
+            .and_then(|_| writer.flush());
+
+        if let Err(error) = written {
+            eprintln!("Unable to write report: {error}");
+        }
+    }
+
+    if wrong_answers > 0 {
+        eprintln!("{wrong_answers} answer(s) don't match {MANIFEST_FILE}");
+        std::process::exit(1);
+    }
+}
+
+fn run_profiles(solutions: &[&dyn Solution], args: &Args) {
+    let matrix = profiles::run_matrix(solutions, args.options.part, args.jobs);
+
+    if matrix.profiles.is_empty() {
+        println!("No day has an input.txt or inputs/<name>.txt");
+        return;
+    }
+
+    print!("{matrix}");
+
+    let wrong_answers = matrix.wrong_answers();
+    for wrong_answer in &wrong_answers {
+        eprintln!("{wrong_answer}");
+    }
+
+    if !wrong_answers.is_empty() {
+        eprintln!(
+            "{} answer(s) don't match {MANIFEST_FILE}",
+            wrong_answers.len()
+        );
+        std::process::exit(1);
+    }
+}
+
+fn bench_puzzles(solutions: &[&dyn Solution], args: &Args, bench_args: &BenchArgs) {
+    let baseline = bench_args.baseline.as_ref().map(|path| {
+        let loaded = fs::read_to_string(path)
+            .map_err(|error| error.to_string())
+            .and_then(|text| Baseline::parse(&text).map_err(|error| error.to_string()));
+
+        loaded.unwrap_or_else(|error| {
+            eprintln!("Unable to load baseline {}: {error}", path.display());
+            std::process::exit(1);
+        })
+    });
+
+    let mut new_baseline = Baseline::default();
+
+    for solution in solutions {
+        let day = solution.day();
+
+        let records = match bench_puzzle(*solution, &args.options, &bench_args.config) {
+            Some(Ok(records)) => records,
+            Some(Err(error)) => {
+                println!("[Day {day}]: malformed input, {error}");
+                continue;
+            }
+            None => {
+                println!("Day {day} {} is missing!", args.options.input.location(day));
+                continue;
+            }
+        };
+
+        for record in &records {
+            let comparison = baseline
+                .as_ref()
+                .and_then(|baseline| baseline.compare(record))
+                .map_or(String::new(), |comparison| format!(" [{comparison}]"));
+
+            println!(
+                "[Day {day}]: {}: {}{comparison}",
+                record.stage, record.stats
+            );
+
+            new_baseline.record(record);
+        }
+    }
+
+    if let Some(path) = &bench_args.save_baseline
+        && let Err(error) = fs::write(path, new_baseline.to_string())
+    {
+        eprintln!("Unable to save baseline {}: {error}", path.display());
+    }
+}
+
+/// A client for the site, logged in with the `AOC_SESSION` cookie.
+fn site_client() -> Client {
+    let Ok(session) = std::env::var("AOC_SESSION") else {
+        eprintln!("Set AOC_SESSION to the session cookie of your adventofcode.com login");
+        std::process::exit(1);
+    };
+    let base_url =
+        std::env::var("AOC_BASE_URL").unwrap_or_else(|_| fetch::DEFAULT_BASE_URL.to_string());
+
+    Client::new(fetch::transport_for(&base_url), session).base_url(base_url)
+}
+
+fn fetch_inputs(days: &[usize], fetch_args: &FetchArgs) {
+    let fetcher = InputFetcher::new(site_client()).cache_dir(&fetch_args.cache_dir);
+
+    for day in days {
+        let path = utilities::data_path(*day, "input.txt");
+
+        if !fetch_args.refresh && path.exists() {
+            println!("[Day {day}]: {} is already there", path.display());
+            continue;
+        }
+
+        let fetched = if fetch_args.refresh {
+            fetcher.refresh(*day)
+        } else {
+            fetcher.fetch(*day)
+        };
+
+        let written = fetched
+            .map_err(|error| error.to_string())
+            .and_then(|contents| {
+                fs::create_dir_all(path.parent().unwrap())
+                    .and_then(|_| fs::write(&path, contents))
+                    .map_err(|error| error.to_string())
+            });
+
+        match written {
+            Ok(()) => println!("[Day {day}]: wrote {}", path.display()),
+            Err(error) => eprintln!("[Day {day}]: unable to fetch input, {error}"),
+        }
+    }
+}
+
+fn submit_answer(day: usize, args: &Args, submit_args: &SubmitArgs) {
+    let part = args.options.part.numbers()[0];
+
+    let answer = match &submit_args.answer {
+        Some(answer) => answer.clone(),
+        None => {
+            let Some(solution) = find_solution(day) else {
+                eprintln!("Day {day} has no solution yet, so pass the answer with '--answer'");
+                std::process::exit(1);
+            };
+
+            // Missing or malformed input has already been reported by the run.
+            match utilities::run_puzzle(solution, &args.options).first() {
+                Some(record) if record.answer.is_ok() => record.answer.clone().unwrap(),
+                _ => std::process::exit(1),
+            }
+        }
+    };
+
+    let log_path = submit_args.cache_dir.join(submit::LOG_FILE);
+    let loaded = match fs::read_to_string(&log_path) {
+        Ok(text) => GuessLog::parse(&text).map_err(|error| error.to_string()),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(GuessLog::default()),
+        Err(error) => Err(error.to_string()),
+    };
+    let mut log = loaded.unwrap_or_else(|error| {
+        eprintln!("Unable to load {}: {error}", log_path.display());
+        std::process::exit(1);
+    });
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.as_secs() as i64);
+
+    let reply = match submit::submit(&site_client(), &mut log, day, part, &answer, now) {
+        Ok(reply) => reply,
+        Err(error) => {
+            eprintln!("[Day {day}]: part {part} answer {answer} {error}");
+            std::process::exit(1);
+        }
+    };
+
+    let saved = fs::create_dir_all(&submit_args.cache_dir)
+        .and_then(|_| fs::write(&log_path, log.to_string()));
+    if let Err(error) = saved {
+        eprintln!("Unable to write {}: {error}", log_path.display());
+    }
+
+    println!(
+        "[Day {day}]: part {part} answer {answer} is {}",
+        reply.outcome
+    );
+    if let Some(cooldown) = reply.cooldown {
+        println!("The site will take another answer in {cooldown:?}");
+    }
+
+    if reply.outcome != Outcome::Correct {
+        std::process::exit(1);
+    }
+}
+
+fn generate_input(solution: &dyn Solution, generate_args: &GenerateArgs) {
+    let seed = generate_args.seed.unwrap_or_else(|| {
+        let seed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |since| since.as_nanos() as u64);
+        eprintln!("Generating day {} with --seed {seed}", solution.day());
+
+        seed
+    });
+
+    match solution.generate(&mut Rng::new(seed), generate_args.size) {
+        Some(contents) => print!("{contents}"),
+        None => {
+            eprintln!("Day {} has no input generator", solution.day());
+            std::process::exit(1);
+        }
+    }
+}
+
+fn load_journal() -> Journal {
+    let loaded = match fs::read_to_string(JOURNAL_FILE) {
+        Ok(text) => Journal::parse(&text),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(Journal::default()),
+        Err(error) => Err(error.to_string()),
+    };
+
+    loaded.unwrap_or_else(|error| {
+        eprintln!("Unable to load {JOURNAL_FILE}: {error}");
+        std::process::exit(1);
+    })
+}
+
+/// Writes the journal back out, along with the `notes.txt` rendering of it.
+fn save_journal(journal: &Journal) {
+    for (path, contents) in [
+        (JOURNAL_FILE, journal.to_string()),
+        (NOTES_FILE, journal.render_notes()),
+    ] {
+        if let Err(error) = fs::write(path, contents) {
+            eprintln!("Unable to save {path}: {error}");
+            std::process::exit(1);
+        }
+    }
+}
+
+fn new_day(day: usize) {
+    let created = scaffold::create_day(day).unwrap_or_else(|error| {
+        eprintln!("{error}");
+        std::process::exit(1);
+    });
+
+    let mut journal = load_journal();
+    journal.add_day(day);
+    save_journal(&journal);
+
+    for path in created
+        .iter()
+        .map(String::as_str)
+        .chain([JOURNAL_FILE, NOTES_FILE])
+    {
+        println!("Wrote {path}");
+    }
+}
+
+fn start_day(day: usize) {
+    let mut journal = load_journal();
+    let now = journal.now();
+
+    if let Err(error) = journal.start_day(day, now) {
+        eprintln!("{error}");
+        std::process::exit(1);
+    }
+
+    save_journal(&journal);
+
+    println!("[Day {day}]: started at {now}");
+}
+
+fn solve_part(solution: &dyn Solution, args: &Args, note: Option<String>) {
+    let day = solution.day();
+    let part = args.options.part.numbers()[0];
+
+    // Missing or malformed input has already been reported by the run.
+    let records = utilities::run_puzzle(solution, &args.options);
+    let Some(record) = records.first() else {
+        std::process::exit(1);
+    };
+    let Ok(answer) = &record.answer else {
+        std::process::exit(1);
+    };
+
+    let mut journal = load_journal();
+    let now = journal.now();
+    let runtime = record.parse_duration + record.duration;
+
+    match journal.solve_part(day, part, now, answer.clone(), runtime) {
+        Ok(entry) => entry.note = note,
+        Err(error) => {
+            eprintln!("{error}");
+            std::process::exit(1);
+        }
+    }
+
+    save_journal(&journal);
+
+    println!("[Day {day}]: part {part} solved at {now}");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registry_is_ordered_and_unique() {
+        assert!(
+            SOLUTIONS
+                .windows(2)
+                .all(|pair| pair[0].day() < pair[1].day())
+        );
+    }
+
+    #[test]
+    fn test_registry_examples_run() {
+        for solution in SOLUTIONS {
+            let contents = utilities::read_file_data(solution.day(), "example.txt");
+
+            assert!(solution.part_1(&contents).is_ok());
+            assert!(solution.part_2(&contents).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_registry_generated_inputs_run() {
+        for solution in SOLUTIONS {
+            let contents = solution.generate(&mut Rng::new(2025), 20).unwrap();
+            let input = solution.parse(&contents).unwrap();
+
+            assert!(solution.solve(&input, 1).is_ok(), "day {}", solution.day());
+            assert!(solution.solve(&input, 2).is_ok(), "day {}", solution.day());
+        }
+    }
+
+    #[test]
+    fn test_manifest_answers() {
+        let failures = SOLUTIONS
+            .iter()
+            .flat_map(|solution| utilities::manifest::check_manifest(*solution))
+            .collect::<Vec<_>>();
+
+        assert!(failures.is_empty(), "{}", failures.join("\n"));
+    }
+
+    #[test]
+    fn test_profile_answers() {
+        let matrix = profiles::run_matrix(SOLUTIONS, utilities::Part::Both, 4);
+
+        let wrong_answers = matrix.wrong_answers();
+        assert!(
+            wrong_answers.is_empty(),
+            "{matrix}{}",
+            wrong_answers.join("\n")
+        );
+    }
+
+    #[test]
+    fn test_journal_renders_notes() {
+        let journal = Journal::parse(&fs::read_to_string(JOURNAL_FILE).unwrap()).unwrap();
+
+        assert_eq!(
+            journal.render_notes(),
+            fs::read_to_string(NOTES_FILE).unwrap()
+        );
+    }
 }
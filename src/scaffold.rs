@@ -0,0 +1,149 @@
+use std::fs;
+use std::path::Path;
+
+use utilities::{inputs, manifest::MANIFEST_FILE};
+
+const TEMPLATE_FILE: &str = "src/template_day.rs";
+const MAIN_FILE: &str = "src/main.rs";
+
+/// Creates `src/dayN.rs` from the template, registers it in `main.rs` and adds an empty example
+/// and answers manifest under `dayN/` in the input root. Returns the paths it wrote, and touches nothing
+/// if any of them already exist.
+pub fn create_day(day: usize) -> Result<Vec<String>, String> {
+    let day_file = format!("src/day{day}.rs");
+    let test_dir = inputs::root()
+        .join(format!("day{day}"))
+        .display()
+        .to_string();
+
+    for path in [&day_file, &test_dir] {
+        if Path::new(path).exists() {
+            return Err(format!("{path} already exists"));
+        }
+    }
+
+    let template = read(TEMPLATE_FILE)?;
+    let main = read(MAIN_FILE)?;
+
+    let day_source = render_template(&template, day)?;
+    let main = register_day(&main, day)?;
+
+    let example_file = format!("{test_dir}/example.txt");
+    let manifest_file = format!("{test_dir}/{MANIFEST_FILE}");
+
+    fs::create_dir_all(&test_dir)
+        .map_err(|error| format!("Unable to create {test_dir}: {error}"))?;
+
+    for (path, contents) in [
+        (&day_file, day_source),
+        (&example_file, String::new()),
+        (&manifest_file, "[\"example.txt\"]\n".to_string()),
+        (&MAIN_FILE.to_string(), main),
+    ] {
+        fs::write(path, contents).map_err(|error| format!("Unable to write {path}: {error}"))?;
+    }
+
+    Ok(vec![
+        day_file,
+        example_file,
+        manifest_file,
+        MAIN_FILE.to_string(),
+    ])
+}
+
+fn read(path: &str) -> Result<String, String> {
+    fs::read_to_string(path).map_err(|error| format!("Unable to read {path}: {error}"))
+}
+
+fn render_template(template: &str, day: usize) -> Result<String, String> {
+    const DAY_CONST: &str = "const DAY: usize = 0;";
+
+    if !template.contains(DAY_CONST) {
+        return Err(format!("{TEMPLATE_FILE} doesn't declare '{DAY_CONST}'"));
+    }
+
+    Ok(template
+        .replace(DAY_CONST, &format!("const DAY: usize = {day};"))
+        .replace("Day0", &format!("Day{day}")))
+}
+
+/// Adds `dayN::DayN` to the `register_days!` list in `main.rs`, keeping it in day order.
+fn register_day(main: &str, day: usize) -> Result<String, String> {
+    const OPEN: &str = "register_days! {\n";
+
+    let start = main
+        .find(OPEN)
+        .map(|index| index + OPEN.len())
+        .ok_or_else(|| format!("{MAIN_FILE} has no 'register_days!' list"))?;
+    let end = start
+        + main[start..]
+            .find('}')
+            .ok_or_else(|| format!("{MAIN_FILE} has an unterminated 'register_days!' list"))?;
+
+    let entry = format!("day{day}::Day{day},");
+    let mut entries = main[start..end]
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>();
+
+    if entries.contains(&entry.as_str()) {
+        return Err(format!("day {day} is already registered"));
+    }
+
+    entries.push(&entry);
+    entries.sort_by_key(|entry| {
+        entry
+            .strip_prefix("day")
+            .and_then(|rest| rest.split("::").next())
+            .and_then(|day| day.parse::<usize>().ok())
+            .unwrap_or(usize::MAX)
+    });
+
+    let list = entries
+        .iter()
+        .map(|entry| format!("    {entry}\n"))
+        .collect::<String>();
+
+    Ok(format!("{}{list}{}", &main[..start], &main[end..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_template() {
+        let template = "const DAY: usize = 0;\n\npub struct Day0;\n\nimpl Puzzle for Day0 {}\n";
+
+        assert_eq!(
+            render_template(template, 12),
+            Ok(
+                "const DAY: usize = 12;\n\npub struct Day12;\n\nimpl Puzzle for Day12 {}\n"
+                    .to_string()
+            )
+        );
+        assert!(render_template("pub struct Day0;", 12).is_err());
+    }
+
+    #[test]
+    fn test_register_day() {
+        let main = "register_days! {\n    day1::Day1,\n    day10::Day10,\n}\n\nfn main() {}\n";
+
+        assert_eq!(
+            register_day(main, 2),
+            Ok("register_days! {\n    day1::Day1,\n    day2::Day2,\n    day10::Day10,\n}\n\nfn main() {}\n".to_string())
+        );
+        assert!(register_day(main, 10).is_err());
+    }
+
+    #[test]
+    fn test_template_renders() {
+        let template = fs::read_to_string(TEMPLATE_FILE).unwrap();
+
+        let source = render_template(&template, 12).unwrap();
+
+        assert!(source.contains("const DAY: usize = 12;"));
+        assert!(source.contains("impl Puzzle for Day12 {"));
+    }
+}
@@ -1,46 +1,41 @@
-const DAY: usize = 0;
-
-pub fn run() {
-    utilities::run_puzzle(DAY, part_1, part_2);
-}
+use utilities::{Answer, Puzzle, PuzzleError};
 
-fn part_1(contents: &str) -> usize {
-    0
-}
-
-fn part_2(contents: &str) -> usize {
-    0
-}
+const DAY: usize = 0;
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+pub struct Day0;
 
-    #[test]
-    fn test_example_part_1() {
-        let contents = utilities::read_file_data(DAY, "example.txt");
+impl Puzzle for Day0 {
+    type Input = Vec<String>;
 
-        assert_eq!(part_1(&contents), 0);
+    fn day(&self) -> usize {
+        DAY
     }
 
-    #[test]
-    fn test_input_part_1() {
-        let contents = utilities::read_file_data(DAY, "input.txt");
+    fn title(&self) -> &'static str {
+        "TBD"
+    }
 
-        assert_eq!(part_1(&contents), 0);
+    fn parse(&self, contents: &str) -> Result<Self::Input, PuzzleError> {
+        parse_input(contents)
     }
 
-    #[test]
-    fn test_example_part_2() {
-        let contents = utilities::read_file_data(DAY, "example.txt");
+    fn part_1(&self, input: &Self::Input) -> Result<Answer, PuzzleError> {
+        Ok(part_1(input).to_string())
+    }
 
-        assert_eq!(part_2(&contents), 0);
+    fn part_2(&self, input: &Self::Input) -> Result<Answer, PuzzleError> {
+        Ok(part_2(input).to_string())
     }
+}
 
-    #[test]
-    fn test_input_part_2() {
-        let contents = utilities::read_file_data(DAY, "input.txt");
+fn parse_input(contents: &str) -> Result<Vec<String>, PuzzleError> {
+    Ok(contents.lines().map(str::to_string).collect())
+}
 
-        assert_eq!(part_2(&contents), 0);
-    }
+fn part_1(_input: &[String]) -> usize {
+    0
+}
+
+fn part_2(_input: &[String]) -> usize {
+    0
 }
@@ -0,0 +1,294 @@
+//! Repeated timing of solvers, with summary statistics and comparison against a saved baseline.
+
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use crate::toml::{Document, Value};
+use crate::{PuzzleError, RunOptions, Solution};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BenchConfig {
+    pub warmup: usize,
+    pub samples: usize,
+    /// Sampling stops early once this much time has been spent, after at least one sample.
+    pub budget: Duration,
+}
+
+impl Default for BenchConfig {
+    fn default() -> Self {
+        Self {
+            warmup: 3,
+            samples: 25,
+            budget: Duration::from_secs(5),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    pub samples: usize,
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub stddev: Duration,
+}
+
+impl Stats {
+    /// Summarises `samples`, which must not be empty.
+    pub fn from_samples(samples: &[Duration]) -> Self {
+        let mut sorted = samples.to_vec();
+        sorted.sort();
+
+        let count = sorted.len();
+        let median = if count.is_multiple_of(2) {
+            (sorted[count / 2 - 1] + sorted[count / 2]) / 2
+        } else {
+            sorted[count / 2]
+        };
+
+        let nanos = sorted
+            .iter()
+            .map(|d| d.as_nanos() as f64)
+            .collect::<Vec<_>>();
+        let mean = nanos.iter().sum::<f64>() / count as f64;
+        let variance = nanos.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / count as f64;
+
+        Self {
+            samples: count,
+            min: sorted[0],
+            median,
+            mean: Duration::from_nanos(mean.round() as u64),
+            stddev: Duration::from_nanos(variance.sqrt().round() as u64),
+        }
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "min {:?}, median {:?}, mean {:?}, stddev {:?} ({} samples)",
+            self.min, self.median, self.mean, self.stddev, self.samples
+        )
+    }
+}
+
+/// Calls `f` `config.warmup` times untimed, then times it until enough samples are collected or
+/// the budget runs out.
+pub fn bench<F, T>(mut f: F, config: &BenchConfig) -> Stats
+where
+    F: FnMut() -> T,
+{
+    for _ in 0..config.warmup {
+        std::hint::black_box(f());
+    }
+
+    let start = Instant::now();
+    let mut samples = vec![];
+
+    while samples.is_empty() || (samples.len() < config.samples && start.elapsed() < config.budget)
+    {
+        let now = Instant::now();
+        std::hint::black_box(f());
+
+        samples.push(now.elapsed());
+    }
+
+    Stats::from_samples(&samples)
+}
+
+/// What a benchmark measured: parsing the input, or solving one part from parsed input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    Parse,
+    Part(usize),
+}
+
+impl Stage {
+    fn key(&self) -> String {
+        match self {
+            Stage::Parse => "parse".to_string(),
+            Stage::Part(part) => format!("part_{part}"),
+        }
+    }
+}
+
+impl fmt::Display for Stage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Stage::Parse => write!(f, "parse"),
+            Stage::Part(part) => write!(f, "part {part}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BenchRecord {
+    pub day: usize,
+    pub stage: Stage,
+    pub stats: Stats,
+}
+
+/// Benchmarks parsing and then the selected parts of `solution`, or returns `None` if its input
+/// is missing.
+pub fn bench_puzzle(
+    solution: &dyn Solution,
+    options: &RunOptions,
+    config: &BenchConfig,
+) -> Option<Result<Vec<BenchRecord>, PuzzleError>> {
+    let day = solution.day();
+    let contents = options.input.read(day)?;
+
+    let input = match solution.parse(&contents) {
+        Ok(input) => input,
+        Err(error) => return Some(Err(error.with_day(day))),
+    };
+
+    let mut records = vec![BenchRecord {
+        day,
+        stage: Stage::Parse,
+        stats: bench(|| solution.parse(&contents), config),
+    }];
+
+    for part in options.part.numbers() {
+        let run = || solution.solve(&input, *part);
+
+        if let Err(error) = run() {
+            return Some(Err(error.with_day(day)));
+        }
+
+        records.push(BenchRecord {
+            day,
+            stage: Stage::Part(*part),
+            stats: bench(run, config),
+        });
+    }
+
+    Some(Ok(records))
+}
+
+/// Median timings from an earlier benchmark run, stored as `[dayN]` tables with
+/// `parse`/`part_1`/`part_2` keys holding nanoseconds.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Baseline {
+    document: Document,
+}
+
+impl Baseline {
+    pub fn parse(text: &str) -> Result<Self, PuzzleError> {
+        Ok(Self {
+            document: Document::parse(text)?,
+        })
+    }
+
+    pub fn get(&self, day: usize, stage: Stage) -> Option<Duration> {
+        let nanos = self
+            .document
+            .table(&format!("day{day}"))?
+            .get(&stage.key())?
+            .as_integer()?;
+
+        Some(Duration::from_nanos(nanos.try_into().ok()?))
+    }
+
+    pub fn record(&mut self, record: &BenchRecord) {
+        let nanos = record.stats.median.as_nanos().min(i64::MAX as u128) as i64;
+
+        self.document
+            .table_mut(&format!("day{}", record.day))
+            .set(&record.stage.key(), Value::Integer(nanos));
+    }
+
+    /// Describes how `median` compares to the baseline, e.g. `"-5.3% vs 19µs"`.
+    pub fn compare(&self, record: &BenchRecord) -> Option<String> {
+        let baseline = self.get(record.day, record.stage)?;
+
+        let change = if baseline.is_zero() {
+            0.0
+        } else {
+            (record.stats.median.as_secs_f64() / baseline.as_secs_f64() - 1.0) * 100.0
+        };
+
+        Some(format!("{change:+.1}% vs {baseline:?}"))
+    }
+}
+
+impl fmt::Display for Baseline {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.document)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn millis(values: &[u64]) -> Vec<Duration> {
+        values.iter().map(|ms| Duration::from_millis(*ms)).collect()
+    }
+
+    #[test]
+    fn test_stats() {
+        let stats = Stats::from_samples(&millis(&[4, 2, 8, 6]));
+
+        assert_eq!(stats.samples, 4);
+        assert_eq!(stats.min, Duration::from_millis(2));
+        assert_eq!(stats.median, Duration::from_millis(5));
+        assert_eq!(stats.mean, Duration::from_millis(5));
+        assert_eq!(stats.stddev, Duration::from_nanos(2_236_068));
+    }
+
+    #[test]
+    fn test_bench_sample_count() {
+        let mut calls = 0;
+        let config = BenchConfig {
+            warmup: 2,
+            samples: 5,
+            budget: Duration::from_secs(60),
+        };
+
+        let stats = bench(|| calls += 1, &config);
+
+        assert_eq!(stats.samples, 5);
+        assert_eq!(calls, 7);
+    }
+
+    #[test]
+    fn test_bench_budget() {
+        let config = BenchConfig {
+            warmup: 0,
+            samples: 1000,
+            budget: Duration::ZERO,
+        };
+
+        assert_eq!(bench(|| (), &config).samples, 1);
+    }
+
+    #[test]
+    fn test_baseline() {
+        let record = BenchRecord {
+            day: 8,
+            stage: Stage::Part(2),
+            stats: Stats::from_samples(&millis(&[450])),
+        };
+
+        let mut baseline = Baseline::default();
+        baseline.record(&record);
+
+        let baseline = Baseline::parse(&baseline.to_string()).unwrap();
+        assert_eq!(
+            baseline.get(8, Stage::Part(2)),
+            Some(Duration::from_millis(450))
+        );
+        assert_eq!(baseline.get(8, Stage::Parse), None);
+
+        let faster = BenchRecord {
+            stats: Stats::from_samples(&millis(&[225])),
+            ..record
+        };
+        assert_eq!(
+            baseline.compare(&faster),
+            Some("-50.0% vs 450ms".to_string())
+        );
+    }
+}
@@ -0,0 +1,87 @@
+use std::error::Error;
+use std::fmt;
+
+/// A problem found while reading puzzle input. Lines and columns are 1-based.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PuzzleError {
+    pub day: Option<usize>,
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl PuzzleError {
+    pub fn new(line: usize, column: usize, message: impl Into<String>) -> Self {
+        Self {
+            day: None,
+            line,
+            column,
+            message: message.into(),
+        }
+    }
+
+    /// Builds an error pointing at `token`, which must be a slice of `line_text`.
+    pub fn at_token(line: usize, line_text: &str, token: &str, message: impl Into<String>) -> Self {
+        Self::new(line, column_of(line_text, token), message)
+    }
+
+    pub fn with_day(self, day: usize) -> Self {
+        Self {
+            day: Some(day),
+            ..self
+        }
+    }
+}
+
+impl fmt::Display for PuzzleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(day) = self.day {
+            write!(f, "day {day}, ")?;
+        }
+
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )
+    }
+}
+
+impl Error for PuzzleError {}
+
+/// Returns the 1-based column at which `token` starts inside `line_text`.
+///
+/// `token` has to be a subslice of `line_text`, e.g. a piece returned by `split`.
+pub fn column_of(line_text: &str, token: &str) -> usize {
+    let offset = (token.as_ptr() as usize).saturating_sub(line_text.as_ptr() as usize);
+
+    line_text
+        .get(..offset)
+        .map_or(offset, |prefix| prefix.chars().count())
+        + 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_column_of() {
+        let line = "L68 R30";
+        let token = line.split(' ').nth(1).unwrap();
+
+        assert_eq!(column_of(line, line), 1);
+        assert_eq!(column_of(line, token), 5);
+    }
+
+    #[test]
+    fn test_display() {
+        let error = PuzzleError::new(3, 1, "unknown direction 'X'");
+
+        assert_eq!(error.to_string(), "line 3, column 1: unknown direction 'X'");
+        assert_eq!(
+            error.with_day(1).to_string(),
+            "day 1, line 3, column 1: unknown direction 'X'"
+        );
+    }
+}
@@ -0,0 +1,696 @@
+//! Downloads personal puzzle inputs from adventofcode.com, keeping a local cache so each input is
+//! only requested once.
+//!
+//! Requests go through a `Transport` so tests can use a local stand-in server. `TcpTransport`
+//! speaks plain HTTP itself; std has no TLS, so `CurlTransport` hands https URLs to `curl`.
+
+use std::fmt;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::net::TcpStream;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com/2025";
+pub const DEFAULT_CACHE_DIR: &str = ".aoc-cache";
+
+/// Minimum time between two requests to the site.
+pub const DEFAULT_MIN_INTERVAL: Duration = Duration::from_secs(3);
+
+const USER_AGENT: &str = "advent_of_code_2025 input fetcher (std-only Rust)";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Request {
+    pub method: &'static str,
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Option<String>,
+}
+
+impl Request {
+    pub fn get(url: impl Into<String>) -> Self {
+        Self {
+            method: "GET",
+            url: url.into(),
+            headers: vec![],
+            body: None,
+        }
+    }
+
+    pub fn post(url: impl Into<String>, body: impl Into<String>) -> Self {
+        Self {
+            method: "POST",
+            body: Some(body.into()),
+            ..Self::get(url)
+        }
+    }
+
+    pub fn header(mut self, name: &str, value: impl Into<String>) -> Self {
+        self.headers.push((name.to_string(), value.into()));
+        self
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl Response {
+    /// Looks up a header, ignoring case.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+pub trait Transport: Send + Sync {
+    fn send(&self, request: &Request) -> io::Result<Response>;
+}
+
+/// Picks `TcpTransport` for `http://` URLs and `CurlTransport` for anything else.
+pub fn transport_for(url: &str) -> Box<dyn Transport> {
+    if url.starts_with("http://") {
+        Box::new(TcpTransport::default())
+    } else {
+        Box::new(CurlTransport)
+    }
+}
+
+/// HTTP/1.1 over a plain `TcpStream`, one connection per request.
+#[derive(Debug, Clone)]
+pub struct TcpTransport {
+    pub timeout: Duration,
+}
+
+impl Default for TcpTransport {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(30),
+        }
+    }
+}
+
+impl Transport for TcpTransport {
+    fn send(&self, request: &Request) -> io::Result<Response> {
+        let (host, path) = split_http_url(&request.url)?;
+
+        let mut stream = TcpStream::connect(host)?;
+        stream.set_read_timeout(Some(self.timeout))?;
+        stream.set_write_timeout(Some(self.timeout))?;
+
+        let mut head = format!(
+            "{} {path} HTTP/1.1\r\nHost: {host}\r\nConnection: close\r\n",
+            request.method
+        );
+        for (name, value) in &request.headers {
+            head.push_str(&format!("{name}: {value}\r\n"));
+        }
+        if let Some(body) = &request.body {
+            head.push_str(&format!("Content-Length: {}\r\n", body.len()));
+        }
+        head.push_str("\r\n");
+
+        stream.write_all(head.as_bytes())?;
+        if let Some(body) = &request.body {
+            stream.write_all(body.as_bytes())?;
+        }
+
+        let mut raw = vec![];
+        stream.read_to_end(&mut raw)?;
+
+        let (mut response, body) = parse_head(&raw)?;
+
+        let body = if response
+            .header("Transfer-Encoding")
+            .is_some_and(|encoding| encoding.eq_ignore_ascii_case("chunked"))
+        {
+            decode_chunked(body)?
+        } else {
+            body.to_vec()
+        };
+        response.body = String::from_utf8_lossy(&body).into_owned();
+
+        Ok(response)
+    }
+}
+
+/// Runs the `curl` command line tool, which brings its own TLS.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CurlTransport;
+
+impl CurlTransport {
+    /// Only the method and URL go on the command line, which any user can read through `ps`. The
+    /// headers, session cookie included, and the body follow on stdin as a config file.
+    fn command(request: &Request) -> Command {
+        let mut command = Command::new("curl");
+        command
+            .args(["--silent", "--show-error", "--include", "--config", "-"])
+            .arg("--request")
+            .arg(request.method)
+            .arg(&request.url);
+
+        command
+    }
+
+    fn config(request: &Request) -> String {
+        let mut config = String::new();
+
+        for (name, value) in &request.headers {
+            config.push_str(&format!(
+                "header = {}\n",
+                curl_string(&format!("{name}: {value}"))
+            ));
+        }
+        if let Some(body) = &request.body {
+            config.push_str(&format!("data-raw = {}\n", curl_string(body)));
+        }
+
+        config
+    }
+}
+
+/// Quotes `text` for a curl config file.
+fn curl_string(text: &str) -> String {
+    let escaped = text
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+        .replace('\t', "\\t");
+
+    format!("\"{escaped}\"")
+}
+
+impl Transport for CurlTransport {
+    fn send(&self, request: &Request) -> io::Result<Response> {
+        let mut child = Self::command(request)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+
+        let mut stdin = child.stdin.take().expect("stdin is piped");
+        stdin.write_all(Self::config(request).as_bytes())?;
+        drop(stdin);
+
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            return Err(io::Error::other(format!(
+                "curl failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+
+        let (mut response, body) = parse_head(&output.stdout)?;
+        response.body = String::from_utf8_lossy(body).into_owned();
+
+        Ok(response)
+    }
+}
+
+fn invalid_data(message: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.into())
+}
+
+/// Splits `http://host[:port]/path` into an address to connect to and the request path.
+fn split_http_url(url: &str) -> io::Result<(&str, &str)> {
+    let rest = url
+        .strip_prefix("http://")
+        .ok_or_else(|| invalid_data(format!("'{url}' is not an http:// URL")))?;
+
+    Ok(match rest.find('/') {
+        Some(index) => (&rest[..index], &rest[index..]),
+        None => (rest, "/"),
+    })
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+/// Reads the status line and headers, returning a response with an empty body along with the raw
+/// bytes of the body, which are only turned into text once any chunks have been put together.
+fn parse_head(raw: &[u8]) -> io::Result<(Response, &[u8])> {
+    let end =
+        find(raw, b"\r\n\r\n").ok_or_else(|| invalid_data("response has no end of headers"))?;
+    let head = String::from_utf8_lossy(&raw[..end]);
+
+    let mut lines = head.lines();
+
+    let status = lines
+        .next()
+        .and_then(|line| line.split_whitespace().nth(1))
+        .and_then(|status| status.parse::<u16>().ok())
+        .ok_or_else(|| invalid_data("response has no status line"))?;
+
+    let headers = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+        .collect();
+
+    let response = Response {
+        status,
+        headers,
+        body: String::new(),
+    };
+
+    Ok((response, &raw[end + 4..]))
+}
+
+fn decode_chunked(body: &[u8]) -> io::Result<Vec<u8>> {
+    let mut decoded = vec![];
+    let mut rest = body;
+
+    loop {
+        let line_end = find(rest, b"\r\n").ok_or_else(|| invalid_data("truncated chunk"))?;
+        let size = String::from_utf8_lossy(&rest[..line_end]);
+        let size = usize::from_str_radix(size.split(';').next().unwrap_or("").trim(), 16)
+            .map_err(|_| invalid_data(format!("invalid chunk size '{size}'")))?;
+        let after = &rest[line_end + 2..];
+
+        if size == 0 {
+            return Ok(decoded);
+        }
+
+        let chunk = after
+            .get(..size)
+            .ok_or_else(|| invalid_data("truncated chunk"))?;
+        decoded.extend_from_slice(chunk);
+
+        rest = after[size..]
+            .strip_prefix(b"\r\n")
+            .unwrap_or(&after[size..]);
+    }
+}
+
+#[derive(Debug)]
+pub enum FetchError {
+    Io(io::Error),
+    /// The site answered with something other than the input, e.g. 400 for a bad session or 404
+    /// before the puzzle unlocks.
+    Status {
+        status: u16,
+        message: String,
+    },
+}
+
+impl fmt::Display for FetchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FetchError::Io(error) => write!(f, "{error}"),
+            FetchError::Status { status, message } => write!(f, "HTTP {status}: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for FetchError {}
+
+impl From<io::Error> for FetchError {
+    fn from(error: io::Error) -> Self {
+        FetchError::Io(error)
+    }
+}
+
+/// A session on the site: the login cookie, where to send requests and how often.
+pub struct Client {
+    transport: Box<dyn Transport>,
+    session: String,
+    base_url: String,
+    min_interval: Duration,
+    last_request: Mutex<Option<Instant>>,
+}
+
+impl Client {
+    pub fn new(transport: Box<dyn Transport>, session: impl Into<String>) -> Self {
+        Self {
+            transport,
+            session: session.into(),
+            base_url: DEFAULT_BASE_URL.to_string(),
+            min_interval: DEFAULT_MIN_INTERVAL,
+            last_request: Mutex::new(None),
+        }
+    }
+
+    pub fn base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    pub fn min_interval(mut self, min_interval: Duration) -> Self {
+        self.min_interval = min_interval;
+        self
+    }
+
+    /// Adds the session cookie and user agent to a request for `path` under the base URL.
+    fn authorize(&self, request: Request) -> Request {
+        request
+            .header("Cookie", format!("session={}", self.session))
+            .header("User-Agent", USER_AGENT)
+    }
+
+    pub fn get(&self, path: &str) -> Request {
+        self.authorize(Request::get(format!("{}{path}", self.base_url)))
+    }
+
+    pub fn post_form(&self, path: &str, body: impl Into<String>) -> Request {
+        self.authorize(Request::post(format!("{}{path}", self.base_url), body))
+            .header("Content-Type", "application/x-www-form-urlencoded")
+    }
+
+    /// Sends `request` once at least `min_interval` has passed since the previous one.
+    pub fn send(&self, request: &Request) -> io::Result<Response> {
+        let mut last_request = self.last_request.lock().unwrap();
+
+        if let Some(last_request) = *last_request {
+            let wait = self.min_interval.saturating_sub(last_request.elapsed());
+            thread::sleep(wait);
+        }
+
+        let response = self.transport.send(request);
+        *last_request = Some(Instant::now());
+
+        response
+    }
+}
+
+pub struct InputFetcher {
+    client: Client,
+    cache_dir: PathBuf,
+}
+
+impl InputFetcher {
+    pub fn new(client: Client) -> Self {
+        Self {
+            client,
+            cache_dir: PathBuf::from(DEFAULT_CACHE_DIR),
+        }
+    }
+
+    pub fn cache_dir(mut self, cache_dir: impl Into<PathBuf>) -> Self {
+        self.cache_dir = cache_dir.into();
+        self
+    }
+
+    fn cache_path(&self, day: usize, extension: &str) -> PathBuf {
+        self.cache_dir
+            .join(format!("day{day}"))
+            .join(format!("input.{extension}"))
+    }
+
+    pub fn cached(&self, day: usize) -> Option<String> {
+        fs::read_to_string(self.cache_path(day, "txt")).ok()
+    }
+
+    /// Returns the input for `day`, from the cache if it has been downloaded before.
+    pub fn fetch(&self, day: usize) -> Result<String, FetchError> {
+        match self.cached(day) {
+            Some(contents) => Ok(contents),
+            None => self.download(day, None),
+        }
+    }
+
+    /// Asks the site again, sending the cached ETag so an unchanged input isn't downloaded twice.
+    pub fn refresh(&self, day: usize) -> Result<String, FetchError> {
+        let etag = self
+            .cached(day)
+            .and_then(|_| fs::read_to_string(self.cache_path(day, "etag")).ok());
+
+        self.download(day, etag)
+    }
+
+    fn download(&self, day: usize, etag: Option<String>) -> Result<String, FetchError> {
+        let mut request = self.client.get(&format!("/day/{day}/input"));
+        if let Some(etag) = etag {
+            request = request.header("If-None-Match", etag);
+        }
+
+        let response = self.client.send(&request)?;
+
+        match response.status {
+            200 => {
+                fs::create_dir_all(self.cache_dir.join(format!("day{day}")))?;
+                fs::write(self.cache_path(day, "txt"), &response.body)?;
+
+                if let Some(etag) = response.header("ETag") {
+                    fs::write(self.cache_path(day, "etag"), etag)?;
+                }
+
+                Ok(response.body)
+            }
+            304 => Ok(self.cached(day).unwrap_or_default()),
+            status => Err(FetchError::Status {
+                status,
+                message: response.body.trim().to_string(),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    use std::io::BufRead;
+    use std::io::BufReader;
+    use std::net::TcpListener;
+    use std::sync::Arc;
+
+    /// A stand-in server on a local port that answers each connection with the next canned
+    /// response and remembers the raw requests it received.
+    pub(crate) struct StandIn {
+        pub url: String,
+        pub requests: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl StandIn {
+        pub(crate) fn start(responses: Vec<String>) -> Self {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            let url = format!("http://{}", listener.local_addr().unwrap());
+            let requests = Arc::new(Mutex::new(vec![]));
+
+            let received = Arc::clone(&requests);
+            thread::spawn(move || {
+                for response in responses {
+                    let (mut stream, _) = listener.accept().unwrap();
+                    let request = read_request(&mut stream);
+                    received.lock().unwrap().push(request);
+
+                    stream.write_all(response.as_bytes()).unwrap();
+                }
+            });
+
+            Self { url, requests }
+        }
+
+        pub(crate) fn requests(&self) -> Vec<String> {
+            self.requests.lock().unwrap().clone()
+        }
+    }
+
+    fn read_request(stream: &mut TcpStream) -> String {
+        let mut reader = BufReader::new(stream);
+        let mut request = String::new();
+        let mut content_length = 0;
+
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+
+            if let Some(length) = line.strip_prefix("Content-Length: ") {
+                content_length = length.trim().parse().unwrap();
+            }
+
+            request.push_str(&line);
+            if line == "\r\n" {
+                break;
+            }
+        }
+
+        let mut body = vec![0; content_length];
+        reader.read_exact(&mut body).unwrap();
+        request.push_str(&String::from_utf8(body).unwrap());
+
+        request
+    }
+
+    pub(crate) fn response(status: &str, headers: &[&str], body: &str) -> String {
+        let mut response = format!("HTTP/1.1 {status}\r\nContent-Length: {}\r\n", body.len());
+        for header in headers {
+            response.push_str(&format!("{header}\r\n"));
+        }
+
+        format!("{response}\r\n{body}")
+    }
+
+    pub(crate) fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("aoc-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+
+        dir
+    }
+
+    pub(crate) fn client(server: &StandIn) -> Client {
+        Client::new(Box::new(TcpTransport::default()), "abc123")
+            .base_url(&server.url)
+            .min_interval(Duration::ZERO)
+    }
+
+    fn fetcher(server: &StandIn, cache_dir: &PathBuf) -> InputFetcher {
+        InputFetcher::new(client(server)).cache_dir(cache_dir)
+    }
+
+    #[test]
+    fn test_fetch_caches_input() {
+        let server = StandIn::start(vec![response("200 OK", &["ETag: \"v1\""], "1\n2\n3\n")]);
+        let cache_dir = temp_dir("fetch");
+        let fetcher = fetcher(&server, &cache_dir);
+
+        assert_eq!(fetcher.fetch(4).unwrap(), "1\n2\n3\n");
+        assert_eq!(fetcher.fetch(4).unwrap(), "1\n2\n3\n");
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 1);
+        assert!(requests[0].starts_with("GET /day/4/input HTTP/1.1\r\n"));
+        assert!(requests[0].contains("Cookie: session=abc123\r\n"));
+        assert_eq!(
+            fs::read_to_string(cache_dir.join("day4/input.etag")).unwrap(),
+            "\"v1\""
+        );
+
+        fs::remove_dir_all(cache_dir).unwrap();
+    }
+
+    #[test]
+    fn test_refresh_sends_etag() {
+        let server = StandIn::start(vec![
+            response("200 OK", &["ETag: \"v1\""], "42\n"),
+            response("304 Not Modified", &[], ""),
+        ]);
+        let cache_dir = temp_dir("refresh");
+        let fetcher = fetcher(&server, &cache_dir);
+
+        fetcher.fetch(1).unwrap();
+        assert_eq!(fetcher.refresh(1).unwrap(), "42\n");
+
+        assert!(server.requests()[1].contains("If-None-Match: \"v1\"\r\n"));
+
+        fs::remove_dir_all(cache_dir).unwrap();
+    }
+
+    #[test]
+    fn test_fetch_error() {
+        let server = StandIn::start(vec![response(
+            "404 Not Found",
+            &[],
+            "Please don't repeatedly request this endpoint before it unlocks!\n",
+        )]);
+        let cache_dir = temp_dir("error");
+
+        let error = fetcher(&server, &cache_dir).fetch(12).unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "HTTP 404: Please don't repeatedly request this endpoint before it unlocks!"
+        );
+        assert!(!cache_dir.join("day12/input.txt").exists());
+    }
+
+    #[test]
+    fn test_rate_limit() {
+        let server = StandIn::start(vec![
+            response("200 OK", &[], "1\n"),
+            response("200 OK", &[], "2\n"),
+        ]);
+        let cache_dir = temp_dir("rate-limit");
+        let fetcher = InputFetcher::new(client(&server).min_interval(Duration::from_millis(100)))
+            .cache_dir(&cache_dir);
+
+        let start = Instant::now();
+        fetcher.fetch(1).unwrap();
+        fetcher.fetch(2).unwrap();
+
+        assert!(start.elapsed() >= Duration::from_millis(100));
+
+        fs::remove_dir_all(cache_dir).unwrap();
+    }
+
+    #[test]
+    fn test_chunked_response() {
+        let server = StandIn::start(vec![
+            "HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n4\r\n1\n2\n\r\n2\r\n3\n\r\n0\r\n\r\n"
+                .to_string(),
+        ]);
+
+        let response = TcpTransport::default()
+            .send(&Request::get(format!("{}/day/1/input", server.url)))
+            .unwrap();
+
+        assert_eq!(response.body, "1\n2\n3\n");
+    }
+
+    #[test]
+    fn test_chunks_split_characters() {
+        // 'é' is two bytes, and a chunk boundary falls between them.
+        assert_eq!(
+            decode_chunked(b"2\r\nx\xc3\r\n2\r\n\xa9y\r\n0\r\n\r\n").unwrap(),
+            "xéy".as_bytes()
+        );
+        assert!(decode_chunked(b"5\r\nab").is_err());
+    }
+
+    #[test]
+    fn test_curl_keeps_headers_off_the_command_line() {
+        let request = Request::post("https://example.com/day/1/answer", "a=\"1\"\\n")
+            .header("Cookie", "session=abc123");
+
+        let command = CurlTransport::command(&request);
+        assert!(
+            command
+                .get_args()
+                .all(|arg| !arg.to_string_lossy().contains("abc123"))
+        );
+
+        assert_eq!(
+            CurlTransport::config(&request),
+            "header = \"Cookie: session=abc123\"\ndata-raw = \"a=\\\"1\\\"\\\\n\"\n"
+        );
+    }
+
+    #[test]
+    fn test_curl_transport() {
+        if Command::new("curl").arg("--version").output().is_err() {
+            let _ = writeln!(
+                io::stderr(),
+                "skipped test_curl_transport: curl is not installed"
+            );
+            return;
+        }
+
+        let server = StandIn::start(vec![response("200 OK", &[], "That's the right answer!")]);
+
+        let response = CurlTransport
+            .send(
+                &Request::post(format!("{}/day/1/answer", server.url), "level=1&answer=3")
+                    .header("Cookie", "session=abc123"),
+            )
+            .unwrap();
+
+        assert_eq!(response.status, 200);
+        assert_eq!(response.body, "That's the right answer!");
+
+        let request = &server.requests()[0];
+        assert!(request.starts_with("POST /day/1/answer HTTP/1.1\r\n"));
+        assert!(request.contains("Cookie: session=abc123\r\n"));
+        assert!(request.ends_with("\r\n\r\nlevel=1&answer=3"));
+    }
+}
@@ -0,0 +1,329 @@
+//! A directed graph whose nodes are named by strings but stored as dense `u32` ids, so walking it
+//! never has to hash or compare the names.
+
+use std::collections::HashMap;
+
+pub type NodeId = u32;
+
+#[derive(Debug, Clone, Default)]
+pub struct Graph {
+    names: Vec<String>,
+    ids: HashMap<String, NodeId>,
+    successors: Vec<Vec<NodeId>>,
+    predecessors: Vec<Vec<NodeId>>,
+}
+
+/// Nodes that lead round in a loop: each feeds the next, and the last feeds the first. The node
+/// with the lowest id comes first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cycle {
+    pub nodes: Vec<NodeId>,
+}
+
+impl Graph {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The id of the node called `name`, adding it if it isn't there yet.
+    pub fn intern(&mut self, name: &str) -> NodeId {
+        if let Some(id) = self.ids.get(name) {
+            return *id;
+        }
+
+        let id = NodeId::try_from(self.names.len()).expect("too many nodes for a u32 id");
+        self.names.push(name.to_string());
+        self.ids.insert(name.to_string(), id);
+        self.successors.push(vec![]);
+        self.predecessors.push(vec![]);
+
+        id
+    }
+
+    pub fn id(&self, name: &str) -> Option<NodeId> {
+        self.ids.get(name).copied()
+    }
+
+    pub fn name(&self, id: NodeId) -> &str {
+        &self.names[id as usize]
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    pub fn nodes(&self) -> impl Iterator<Item = NodeId> + use<> {
+        0..self.names.len() as NodeId
+    }
+
+    pub fn add_edge(&mut self, from: NodeId, to: NodeId) {
+        self.successors[from as usize].push(to);
+        self.predecessors[to as usize].push(from);
+    }
+
+    pub fn successors(&self, id: NodeId) -> &[NodeId] {
+        &self.successors[id as usize]
+    }
+
+    pub fn predecessors(&self, id: NodeId) -> &[NodeId] {
+        &self.predecessors[id as usize]
+    }
+
+    /// Every node, ordered so that each edge goes from an earlier node to a later one.
+    pub fn topological_order(&self) -> Result<Vec<NodeId>, Cycle> {
+        self.topological_order_of(&vec![true; self.len()])
+    }
+
+    /// Which nodes can be reached from `start` by following edges, `start` included.
+    pub fn reachable_from(&self, start: NodeId) -> Vec<bool> {
+        self.flood(start, &self.successors)
+    }
+
+    /// Which nodes can reach `end` by following edges, `end` included.
+    pub fn reaching(&self, end: NodeId) -> Vec<bool> {
+        self.flood(end, &self.predecessors)
+    }
+
+    /// Counts the paths from `from` to `to`, working through the nodes in topological order. Only
+    /// the nodes on some path between the two are looked at, so loops elsewhere don't matter.
+    pub fn count_paths(&self, from: NodeId, to: NodeId) -> Result<usize, Cycle> {
+        if from == to {
+            return Ok(1);
+        }
+
+        let reaching = self.reaching(to);
+        let on_path = self
+            .reachable_from(from)
+            .iter()
+            .zip(&reaching)
+            .map(|(reachable, reaching)| *reachable && *reaching)
+            .collect::<Vec<_>>();
+
+        let mut counts = vec![0; self.len()];
+        counts[from as usize] = 1;
+
+        for id in self.topological_order_of(&on_path)? {
+            let count = counts[id as usize];
+
+            for successor in self.successors(id) {
+                counts[*successor as usize] += count;
+            }
+        }
+
+        Ok(counts[to as usize])
+    }
+
+    /// Counts the paths from `from` to `to` that visit every node in `via`. Without loops, a
+    /// path visits them in a single order, so this adds up the paths through each order in turn.
+    pub fn count_paths_via(
+        &self,
+        from: NodeId,
+        to: NodeId,
+        via: &[NodeId],
+    ) -> Result<usize, Cycle> {
+        if via.is_empty() {
+            return self.count_paths(from, to);
+        }
+
+        let mut total = 0;
+        let mut via = via.to_vec();
+
+        for i in 0..via.len() {
+            via.swap(0, i);
+
+            let to_waypoint = self.count_paths(from, via[0])?;
+            if to_waypoint > 0 {
+                total += to_waypoint * self.count_paths_via(via[0], to, &via[1..])?;
+            }
+
+            via.swap(0, i);
+        }
+
+        Ok(total)
+    }
+
+    fn flood(&self, start: NodeId, edges: &[Vec<NodeId>]) -> Vec<bool> {
+        let mut seen = vec![false; self.len()];
+        let mut stack = vec![start];
+        seen[start as usize] = true;
+
+        while let Some(id) = stack.pop() {
+            for next in &edges[id as usize] {
+                if !seen[*next as usize] {
+                    seen[*next as usize] = true;
+                    stack.push(*next);
+                }
+            }
+        }
+
+        seen
+    }
+
+    /// Kahn's algorithm over the nodes marked in `include`, ignoring edges to or from the rest.
+    fn topological_order_of(&self, include: &[bool]) -> Result<Vec<NodeId>, Cycle> {
+        let mut incoming = self
+            .nodes()
+            .map(|id| {
+                self.predecessors(id)
+                    .iter()
+                    .filter(|from| include[**from as usize])
+                    .count()
+            })
+            .collect::<Vec<_>>();
+
+        let mut ready = self
+            .nodes()
+            .filter(|id| include[*id as usize] && incoming[*id as usize] == 0)
+            .collect::<Vec<_>>();
+        let mut order = vec![];
+
+        while let Some(id) = ready.pop() {
+            order.push(id);
+
+            for successor in self.successors(id) {
+                if !include[*successor as usize] {
+                    continue;
+                }
+
+                incoming[*successor as usize] -= 1;
+                if incoming[*successor as usize] == 0 {
+                    ready.push(*successor);
+                }
+            }
+        }
+
+        if order.len() == include.iter().filter(|include| **include).count() {
+            return Ok(order);
+        }
+
+        // Every node left over still has an edge in from another one left over, so walking those
+        // edges backwards has to come round to a node it has already passed.
+        let left_over = |id: &NodeId| include[*id as usize] && incoming[*id as usize] > 0;
+        let mut walked = vec![self.nodes().find(left_over).unwrap()];
+
+        loop {
+            let last = *walked.last().unwrap();
+            let previous = *self
+                .predecessors(last)
+                .iter()
+                .find(|id| left_over(id))
+                .unwrap();
+
+            if let Some(start) = walked.iter().position(|id| *id == previous) {
+                let mut nodes = walked.split_off(start);
+                nodes.reverse();
+
+                let lowest = (0..nodes.len()).min_by_key(|i| nodes[*i]).unwrap();
+                nodes.rotate_left(lowest);
+
+                return Err(Cycle { nodes });
+            }
+
+            walked.push(previous);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph(edges: &[(&str, &str)]) -> Graph {
+        let mut graph = Graph::new();
+
+        for (from, to) in edges {
+            let (from, to) = (graph.intern(from), graph.intern(to));
+            graph.add_edge(from, to);
+        }
+
+        graph
+    }
+
+    fn names<'a>(graph: &'a Graph, ids: &[NodeId]) -> Vec<&'a str> {
+        ids.iter().map(|id| graph.name(*id)).collect()
+    }
+
+    #[test]
+    fn test_intern() {
+        let mut graph = Graph::new();
+
+        assert_eq!(graph.intern("svr"), 0);
+        assert_eq!(graph.intern("out"), 1);
+        assert_eq!(graph.intern("svr"), 0);
+        assert_eq!(graph.len(), 2);
+        assert_eq!(graph.id("out"), Some(1));
+        assert_eq!(graph.id("you"), None);
+        assert_eq!(graph.name(1), "out");
+    }
+
+    #[test]
+    fn test_topological_order() {
+        let graph = graph(&[("a", "b"), ("a", "c"), ("c", "b"), ("b", "d"), ("e", "d")]);
+
+        let order = graph.topological_order().unwrap();
+        let position = |name| order.iter().position(|id| graph.name(*id) == name).unwrap();
+
+        assert_eq!(order.len(), 5);
+        assert!(position("a") < position("c") && position("c") < position("b"));
+        assert!(position("b") < position("d") && position("e") < position("d"));
+    }
+
+    #[test]
+    fn test_topological_order_finds_cycles() {
+        let graph = graph(&[("a", "b"), ("b", "c"), ("c", "d"), ("d", "b"), ("d", "e")]);
+
+        let cycle = graph.topological_order().unwrap_err();
+        assert_eq!(names(&graph, &cycle.nodes), vec!["b", "c", "d"]);
+
+        let graph = self::graph(&[("a", "a")]);
+        assert_eq!(graph.topological_order(), Err(Cycle { nodes: vec![0] }));
+    }
+
+    #[test]
+    fn test_reachability() {
+        let graph = graph(&[("a", "b"), ("b", "c"), ("d", "b")]);
+
+        assert_eq!(graph.reachable_from(0), vec![true, true, true, false]);
+        assert_eq!(graph.reaching(1), vec![true, true, false, true]);
+    }
+
+    #[test]
+    fn test_count_paths() {
+        let graph = graph(&[
+            ("a", "b"),
+            ("a", "c"),
+            ("b", "d"),
+            ("c", "d"),
+            ("d", "e"),
+            ("c", "e"),
+            ("x", "y"),
+            ("y", "x"),
+            ("x", "e"),
+        ]);
+        let id = |name| graph.id(name).unwrap();
+
+        // The loop between x and y is never on a path from a.
+        assert_eq!(graph.count_paths(id("a"), id("e")), Ok(3));
+        assert_eq!(graph.count_paths(id("e"), id("a")), Ok(0));
+        assert_eq!(graph.count_paths(id("a"), id("a")), Ok(1));
+        assert!(graph.count_paths(id("x"), id("e")).is_err());
+
+        assert_eq!(graph.count_paths_via(id("a"), id("e"), &[id("d")]), Ok(2));
+        assert_eq!(
+            graph.count_paths_via(id("a"), id("e"), &[id("d"), id("c")]),
+            Ok(1)
+        );
+        assert_eq!(
+            graph.count_paths_via(id("a"), id("e"), &[id("c"), id("d")]),
+            Ok(1)
+        );
+        assert_eq!(
+            graph.count_paths_via(id("a"), id("e"), &[id("b"), id("c")]),
+            Ok(0)
+        );
+    }
+}
@@ -0,0 +1,251 @@
+use std::fmt;
+use std::ops::{Index, IndexMut};
+
+use crate::PuzzleError;
+use crate::parse;
+
+/// A `(row, col)` position inside a grid.
+pub type Position = (usize, usize);
+
+const OFFSETS_4: [(isize, isize); 4] = [(-1, 0), (0, -1), (0, 1), (1, 0)];
+
+const OFFSETS_8: [(isize, isize); 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, -1),
+    (0, 1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+];
+
+/// A rectangular grid stored row by row in a single `Vec`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+
+    pub fn transpose(&self) -> Self {
+        let cells = (0..self.width)
+            .flat_map(|col| self.column(col).cloned())
+            .collect();
+
+        Self {
+            width: self.height,
+            height: self.width,
+            cells,
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    /// Builds a grid from its rows, which all have to be the same length.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Result<Self, PuzzleError> {
+        let height = rows.len();
+        let width = rows.first().map_or(0, |row| row.len());
+
+        if let Some(r) = rows.iter().position(|row| row.len() != width) {
+            return Err(PuzzleError::new(
+                r + 1,
+                rows[r].len().min(width) + 1,
+                format!("row has {} cells, expected {width}", rows[r].len()),
+            ));
+        }
+
+        Ok(Self {
+            width,
+            height,
+            cells: rows.into_iter().flatten().collect(),
+        })
+    }
+
+    /// Parses one row per line, mapping every character through `cell`.
+    pub fn parse(text: &str, cell: impl Fn(char) -> Option<T>) -> Result<Self, PuzzleError> {
+        Self::from_rows(parse::grid(text, cell)?)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, (row, col): Position) -> Option<&T> {
+        (row < self.height && col < self.width).then(|| &self.cells[row * self.width + col])
+    }
+
+    pub fn get_mut(&mut self, (row, col): Position) -> Option<&mut T> {
+        (row < self.height && col < self.width).then(|| &mut self.cells[row * self.width + col])
+    }
+
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.cells[row * self.width..(row + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        (0..self.height).map(|row| self.row(row))
+    }
+
+    pub fn column(&self, col: usize) -> impl Iterator<Item = &T> {
+        self.cells.iter().skip(col).step_by(self.width.max(1))
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(|col| self.column(col))
+    }
+
+    pub fn positions(&self) -> impl Iterator<Item = Position> + use<T> {
+        let width = self.width;
+
+        (0..self.height).flat_map(move |row| (0..width).map(move |col| (row, col)))
+    }
+
+    /// Iterates over every cell along with its position, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Position, &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    /// The up to 4 orthogonally adjacent positions.
+    pub fn neighbors_4(&self, position: Position) -> impl Iterator<Item = Position> + use<T> {
+        self.offset_positions(position, &OFFSETS_4)
+    }
+
+    /// The up to 8 adjacent positions, including diagonals.
+    pub fn neighbors_8(&self, position: Position) -> impl Iterator<Item = Position> + use<T> {
+        self.offset_positions(position, &OFFSETS_8)
+    }
+
+    fn offset_positions(
+        &self,
+        (row, col): Position,
+        offsets: &'static [(isize, isize)],
+    ) -> impl Iterator<Item = Position> + use<T> {
+        let (width, height) = (self.width, self.height);
+
+        offsets.iter().filter_map(move |(dr, dc)| {
+            let r = row.checked_add_signed(*dr)?;
+            let c = col.checked_add_signed(*dc)?;
+
+            (r < height && c < width).then_some((r, c))
+        })
+    }
+
+    pub fn find(&self, value: &T) -> Option<Position>
+    where
+        T: PartialEq,
+    {
+        self.find_all(value).next()
+    }
+
+    pub fn find_all<'a>(&'a self, value: &'a T) -> impl Iterator<Item = Position> + 'a
+    where
+        T: PartialEq,
+    {
+        self.iter()
+            .filter(move |(_, cell)| *cell == value)
+            .map(|(position, _)| position)
+    }
+}
+
+impl Grid<char> {
+    pub fn parse_chars(text: &str) -> Result<Self, PuzzleError> {
+        Self::parse(text, Some)
+    }
+}
+
+impl<T> Index<Position> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, position: Position) -> &T {
+        self.get(position)
+            .unwrap_or_else(|| panic!("{position:?} is outside the grid"))
+    }
+}
+
+impl<T> IndexMut<Position> for Grid<T> {
+    fn index_mut(&mut self, position: Position) -> &mut T {
+        self.get_mut(position)
+            .unwrap_or_else(|| panic!("{position:?} is outside the grid"))
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (r, row) in self.rows().enumerate() {
+            if r != 0 {
+                writeln!(f)?;
+            }
+
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_display() {
+        let grid = Grid::parse_chars("..@\n@.S").unwrap();
+
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[(1, 2)], 'S');
+        assert_eq!(grid.find(&'S'), Some((1, 2)));
+        assert_eq!(
+            grid.find_all(&'@').collect::<Vec<_>>(),
+            vec![(0, 2), (1, 0)]
+        );
+        assert_eq!(grid.to_string(), "..@\n@.S");
+    }
+
+    #[test]
+    fn test_ragged_rows() {
+        let result = Grid::parse_chars("...\n..\n...");
+
+        assert_eq!(
+            result,
+            Err(PuzzleError::new(2, 3, "row has 2 cells, expected 3"))
+        );
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let grid = Grid::new(3, 3, 0);
+
+        assert_eq!(
+            grid.neighbors_4((0, 0)).collect::<Vec<_>>(),
+            vec![(0, 1), (1, 0)]
+        );
+        assert_eq!(grid.neighbors_8((1, 1)).count(), 8);
+        assert_eq!(grid.neighbors_8((2, 2)).count(), 3);
+    }
+
+    #[test]
+    fn test_rows_columns_transpose() {
+        let grid = Grid::parse_chars("abc\ndef").unwrap();
+
+        assert_eq!(grid.row(1), &['d', 'e', 'f']);
+        assert_eq!(grid.column(1).collect::<String>(), "be");
+        assert_eq!(grid.columns().count(), 3);
+        assert_eq!(grid.transpose().to_string(), "ad\nbe\ncf");
+    }
+}
@@ -0,0 +1,262 @@
+//! Where the `dayN/` directories of examples, inputs and answers live.
+//!
+//! The root is the first of: `--input-root`, `AOC_INPUT_ROOT`, `input_root` in an `aoc.toml` found
+//! in the current directory or one of its parents, `$CARGO_MANIFEST_DIR/test_files`, and finally
+//! `test_files` in the current directory. A named input set, chosen by `--input-set`,
+//! `AOC_INPUT_SET` or `input_set` in `aoc.toml`, replaces the root with the directory `aoc.toml`
+//! lists for that name. The command line beats the environment, which beats `aoc.toml`, and at
+//! each of those a set beats a root:
+//!
+//! ```toml
+//! input_root = "test_files"
+//! input_set = "alice"
+//!
+//! [input_sets]
+//! alice = "../alice/test_files"
+//! ```
+//!
+//! Relative paths in `aoc.toml` are relative to the file itself.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use crate::toml::{Document, Value};
+
+pub const CONFIG_FILE: &str = "aoc.toml";
+pub const DEFAULT_DIR: &str = "test_files";
+
+static ROOT: OnceLock<PathBuf> = OnceLock::new();
+
+/// What the command line asked for; anything left out falls back to the environment and config.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct InputSettings {
+    pub root: Option<PathBuf>,
+    pub set: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct Config {
+    input_root: Option<PathBuf>,
+    input_set: Option<String>,
+    input_sets: Vec<(String, PathBuf)>,
+}
+
+impl Config {
+    fn parse(text: &str, dir: &Path) -> Result<Self, String> {
+        let document = Document::parse(text).map_err(|error| error.to_string())?;
+        let mut config = Config::default();
+
+        let string = |key: &str, value: &Value| {
+            value
+                .as_str()
+                .map(str::to_string)
+                .ok_or_else(|| format!("'{key}' should be a string"))
+        };
+
+        for table in &document.tables {
+            for (key, value) in &table.entries {
+                match (table.name.as_str(), key.as_str()) {
+                    ("", "input_root") => config.input_root = Some(dir.join(string(key, value)?)),
+                    ("", "input_set") => config.input_set = Some(string(key, value)?),
+                    ("input_sets", name) => config
+                        .input_sets
+                        .push((name.to_string(), dir.join(string(key, value)?))),
+                    ("", key) => return Err(format!("unknown key '{key}'")),
+                    (table, key) => return Err(format!("unknown key '{key}' in [{table}]")),
+                }
+            }
+        }
+
+        Ok(config)
+    }
+
+    /// Reads the nearest `aoc.toml` at or above `dir`, if there is one.
+    fn find(dir: &Path) -> Result<Option<Self>, String> {
+        let Some(path) = dir
+            .ancestors()
+            .map(|dir| dir.join(CONFIG_FILE))
+            .find(|path| path.is_file())
+        else {
+            return Ok(None);
+        };
+
+        let text = fs::read_to_string(&path)
+            .map_err(|error| format!("Unable to read {}: {error}", path.display()))?;
+
+        Config::parse(&text, path.parent().unwrap_or(dir))
+            .map(Some)
+            .map_err(|error| format!("{}: {error}", path.display()))
+    }
+}
+
+/// Works out the input root from `settings`, the environment as seen through `var`, and the
+/// config file nearest `current_dir`.
+pub fn resolve(
+    settings: &InputSettings,
+    var: impl Fn(&str) -> Option<String>,
+    current_dir: &Path,
+) -> Result<PathBuf, String> {
+    let config = Config::find(current_dir)?.unwrap_or_default();
+
+    let choices = [
+        (settings.set.clone(), settings.root.clone()),
+        (
+            var("AOC_INPUT_SET"),
+            var("AOC_INPUT_ROOT").map(PathBuf::from),
+        ),
+        (config.input_set, config.input_root),
+    ];
+
+    for (set, root) in choices {
+        if let Some(set) = set {
+            return config
+                .input_sets
+                .into_iter()
+                .find(|(name, _)| *name == set)
+                .map(|(_, root)| root)
+                .ok_or_else(|| {
+                    format!("unknown input set '{set}', add it to [input_sets] in {CONFIG_FILE}")
+                });
+        }
+
+        if let Some(root) = root {
+            return Ok(current_dir.join(root));
+        }
+    }
+
+    let root = var("CARGO_MANIFEST_DIR")
+        .map(|dir| Path::new(&dir).join(DEFAULT_DIR))
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_DIR));
+
+    Ok(current_dir.join(root))
+}
+
+/// Fixes the input root for the rest of the process. Has no effect once the root has been used.
+pub fn set_root(root: PathBuf) {
+    let _ = ROOT.set(root);
+}
+
+/// The input root, resolved from the environment on first use if `set_root` wasn't called.
+pub fn root() -> &'static Path {
+    ROOT.get_or_init(|| {
+        let current_dir = std::env::current_dir().unwrap_or_default();
+
+        resolve(
+            &InputSettings::default(),
+            |name| std::env::var(name).ok(),
+            &current_dir,
+        )
+        .unwrap_or_else(|error| panic!("{error}"))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::fetch::tests::temp_dir;
+
+    fn no_vars(_: &str) -> Option<String> {
+        None
+    }
+
+    #[test]
+    fn test_resolve_defaults() {
+        let dir = temp_dir("inputs_defaults");
+        fs::create_dir_all(&dir).unwrap();
+
+        assert_eq!(
+            resolve(&InputSettings::default(), no_vars, &dir),
+            Ok(dir.join("test_files"))
+        );
+        assert_eq!(
+            resolve(
+                &InputSettings::default(),
+                |name| (name == "CARGO_MANIFEST_DIR").then(|| "/src/aoc".to_string()),
+                &dir
+            ),
+            Ok(PathBuf::from("/src/aoc/test_files"))
+        );
+    }
+
+    #[test]
+    fn test_resolve_precedence() {
+        let dir = temp_dir("inputs_precedence");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join(CONFIG_FILE), "input_root = \"from_config\"\n").unwrap();
+        let nested = dir.join("src/deep");
+        fs::create_dir_all(&nested).unwrap();
+
+        let env = |name: &str| (name == "AOC_INPUT_ROOT").then(|| "/from_env".to_string());
+        let flag = InputSettings {
+            root: Some(PathBuf::from("from_flag")),
+            set: None,
+        };
+
+        assert_eq!(resolve(&flag, env, &nested), Ok(nested.join("from_flag")));
+        assert_eq!(
+            resolve(&InputSettings::default(), env, &nested),
+            Ok(PathBuf::from("/from_env"))
+        );
+        assert_eq!(
+            resolve(&InputSettings::default(), no_vars, &nested),
+            Ok(dir.join("from_config"))
+        );
+    }
+
+    #[test]
+    fn test_resolve_input_sets() {
+        let dir = temp_dir("inputs_sets");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join(CONFIG_FILE),
+            "input_set = \"alice\"\n\n[input_sets]\nalice = \"alice\"\nbob = \"/shared/bob\"\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            resolve(&InputSettings::default(), no_vars, &dir),
+            Ok(dir.join("alice"))
+        );
+
+        let bob = InputSettings {
+            root: None,
+            set: Some("bob".to_string()),
+        };
+        assert_eq!(
+            resolve(&bob, no_vars, &dir),
+            Ok(PathBuf::from("/shared/bob"))
+        );
+
+        let carol = |name: &str| (name == "AOC_INPUT_SET").then(|| "carol".to_string());
+        assert!(resolve(&InputSettings::default(), carol, &dir).is_err());
+
+        // A root asked for explicitly beats the set that's only the config's default.
+        let explicit_root = InputSettings {
+            root: Some(PathBuf::from("/x")),
+            set: None,
+        };
+        assert_eq!(
+            resolve(&explicit_root, no_vars, &dir),
+            Ok(PathBuf::from("/x"))
+        );
+
+        let env_root = |name: &str| (name == "AOC_INPUT_ROOT").then(|| "/y".to_string());
+        assert_eq!(
+            resolve(&InputSettings::default(), env_root, &dir),
+            Ok(PathBuf::from("/y"))
+        );
+        assert_eq!(
+            resolve(&bob, env_root, &dir),
+            Ok(PathBuf::from("/shared/bob"))
+        );
+    }
+
+    #[test]
+    fn test_config_rejects_unknown_keys() {
+        assert!(Config::parse("inputs = \"x\"\n", Path::new(".")).is_err());
+        assert!(Config::parse("[input_sets]\nalice = 3\n", Path::new(".")).is_err());
+        assert!(Config::parse("[sets]\nalice = \"a\"\n", Path::new(".")).is_err());
+    }
+}
@@ -0,0 +1,609 @@
+//! The progress journal: when each day was started, and when, with what answer and how fast each
+//! part was solved. Stored as TOML and rendered into the older free-form `notes.txt` layout.
+//!
+//! ```toml
+//! utc_offset = "-08:00"
+//!
+//! [day1]
+//! started = "2025-11-30 21:00:00"
+//! part_1_solved = "2025-11-30 21:11:00"
+//! part_1_answer = "1097"
+//! part_1_runtime_ns = 967602
+//! part_1_elapsed = "00:10:57"
+//! part_1_note = "optional, may span lines"
+//! ```
+
+use std::fmt;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::Answer;
+use crate::toml::{Document, Table, Value};
+
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+/// A wall-clock time in the journal's time zone, stored as seconds since 1970-01-01 00:00:00.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Timestamp(i64);
+
+impl Timestamp {
+    pub fn from_unix(seconds: i64, utc_offset_minutes: i32) -> Self {
+        Self(seconds + utc_offset_minutes as i64 * 60)
+    }
+
+    pub fn now(utc_offset_minutes: i32) -> Self {
+        let seconds = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs() as i64);
+
+        Self::from_unix(seconds, utc_offset_minutes)
+    }
+
+    /// Parses `YYYY-MM-DD HH:MM:SS`.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let error = || format!("invalid timestamp '{text}', expected 'YYYY-MM-DD HH:MM:SS'");
+
+        let (date, time) = text.split_once(' ').ok_or_else(error)?;
+        let date = split_numbers(date, '-').ok_or_else(error)?;
+        let time = split_numbers(time, ':').ok_or_else(error)?;
+
+        let &[year, month, day] = date.as_slice() else {
+            return Err(error());
+        };
+        let &[hour, minute, second] = time.as_slice() else {
+            return Err(error());
+        };
+
+        let days = days_from_civil(year, month, day);
+        if civil_from_days(days) != (year, month, day) || hour > 23 || minute > 59 || second > 59 {
+            return Err(error());
+        }
+
+        Ok(Self(
+            days * SECONDS_PER_DAY + hour * 3600 + minute * 60 + second,
+        ))
+    }
+
+    fn date(&self) -> (i64, i64, i64) {
+        civil_from_days(self.0.div_euclid(SECONDS_PER_DAY))
+    }
+
+    fn time(&self) -> (i64, i64, i64) {
+        let seconds = self.0.rem_euclid(SECONDS_PER_DAY);
+
+        (seconds / 3600, seconds / 60 % 60, seconds % 60)
+    }
+
+    /// The `notes.txt` form, e.g. `2025-12-1 9:54pm`.
+    pub fn to_notes_string(&self) -> String {
+        let (year, month, day) = self.date();
+        let (hour, minute, _) = self.time();
+
+        let suffix = if hour < 12 { "am" } else { "pm" };
+        let hour = match hour % 12 {
+            0 => 12,
+            hour => hour,
+        };
+
+        format!("{year}-{month}-{day} {hour}:{minute:02}{suffix}")
+    }
+}
+
+impl fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (year, month, day) = self.date();
+        let (hour, minute, second) = self.time();
+
+        write!(
+            f,
+            "{year:04}-{month:02}-{day:02} {hour:02}:{minute:02}:{second:02}"
+        )
+    }
+}
+
+fn split_numbers(text: &str, separator: char) -> Option<Vec<i64>> {
+    text.split(separator)
+        .map(|piece| {
+            piece
+                .bytes()
+                .all(|b| b.is_ascii_digit())
+                .then(|| piece.parse().ok())
+                .flatten()
+        })
+        .collect()
+}
+
+// Conversions between days since 1970-01-01 and proleptic Gregorian dates, from
+// http://howardhinnant.github.io/date_algorithms.html
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    era * 146097 + day_of_era - 719468
+}
+
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days - era * 146097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    (year, month, day)
+}
+
+fn parse_utc_offset(text: &str) -> Option<i32> {
+    let (sign, rest) = match text.split_at_checked(1)? {
+        ("+", rest) => (1, rest),
+        ("-", rest) => (-1, rest),
+        _ => return None,
+    };
+
+    let (hours, minutes) = rest.split_once(':')?;
+    let (hours, minutes) = (hours.parse::<i32>().ok()?, minutes.parse::<i32>().ok()?);
+
+    (hours < 24 && minutes < 60).then_some(sign * (hours * 60 + minutes))
+}
+
+fn format_utc_offset(minutes: i32) -> String {
+    let sign = if minutes < 0 { '-' } else { '+' };
+    let minutes = minutes.abs();
+
+    format!("{sign}{:02}:{:02}", minutes / 60, minutes % 60)
+}
+
+/// Formats the time between starting a day and solving a part, as `notes.txt` did.
+pub fn format_elapsed(started: Timestamp, solved: Timestamp) -> String {
+    let seconds = solved.0 - started.0;
+
+    if seconds >= SECONDS_PER_DAY {
+        return "> 24 hours".to_string();
+    }
+
+    let seconds = seconds.max(0);
+
+    format!(
+        "{:02}:{:02}:{:02}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartEntry {
+    pub solved: Timestamp,
+    pub answer: Answer,
+    pub runtime: Option<Duration>,
+    /// Free text rather than a duration, since older entries say things like `"> 24 hours"`.
+    pub elapsed: Option<String>,
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DayEntry {
+    pub day: usize,
+    pub started: Option<Timestamp>,
+    pub parts: [Option<PartEntry>; 2],
+}
+
+impl DayEntry {
+    fn new(day: usize) -> Self {
+        Self {
+            day,
+            started: None,
+            parts: [None, None],
+        }
+    }
+
+    pub fn part(&self, part: usize) -> Option<&PartEntry> {
+        self.parts.get(part.checked_sub(1)?)?.as_ref()
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Journal {
+    /// Minutes east of UTC that timestamps are recorded in.
+    pub utc_offset: i32,
+    /// Kept sorted by day.
+    pub days: Vec<DayEntry>,
+}
+
+const PART_KEYS: [&str; 5] = ["solved", "answer", "runtime_ns", "elapsed", "note"];
+
+impl Journal {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let document = Document::parse(text).map_err(|error| error.to_string())?;
+
+        let mut journal = Journal::default();
+
+        for table in &document.tables {
+            if table.name.is_empty() {
+                journal.utc_offset = parse_header(table)?;
+                continue;
+            }
+
+            let day = table
+                .name
+                .strip_prefix("day")
+                .and_then(|day| day.parse::<usize>().ok())
+                .filter(|day| *day > 0)
+                .ok_or_else(|| format!("invalid table [{}], expected [dayN]", table.name))?;
+
+            if journal.day(day).is_some() {
+                return Err(format!("day {day} is listed twice"));
+            }
+
+            journal.days.push(parse_day(day, table)?);
+        }
+
+        journal.days.sort_by_key(|entry| entry.day);
+
+        Ok(journal)
+    }
+
+    pub fn day(&self, day: usize) -> Option<&DayEntry> {
+        self.days.iter().find(|entry| entry.day == day)
+    }
+
+    fn day_mut(&mut self, day: usize) -> &mut DayEntry {
+        let index = match self.days.binary_search_by_key(&day, |entry| entry.day) {
+            Ok(index) => index,
+            Err(index) => {
+                self.days.insert(index, DayEntry::new(day));
+                index
+            }
+        };
+
+        &mut self.days[index]
+    }
+
+    /// Adds an empty entry for `day` if it doesn't have one yet.
+    pub fn add_day(&mut self, day: usize) {
+        self.day_mut(day);
+    }
+
+    pub fn now(&self) -> Timestamp {
+        Timestamp::now(self.utc_offset)
+    }
+
+    pub fn start_day(&mut self, day: usize, at: Timestamp) -> Result<(), String> {
+        let entry = self.day_mut(day);
+
+        if let Some(started) = entry.started {
+            return Err(format!("day {day} was already started at {started}"));
+        }
+
+        entry.started = Some(at);
+
+        Ok(())
+    }
+
+    /// Records `part` of `day` as solved at `at`, working out the elapsed time from when the day
+    /// was started.
+    pub fn solve_part(
+        &mut self,
+        day: usize,
+        part: usize,
+        at: Timestamp,
+        answer: Answer,
+        runtime: Duration,
+    ) -> Result<&mut PartEntry, String> {
+        let entry = self.day_mut(day);
+
+        let Some(slot) = entry.parts.get_mut(part.wrapping_sub(1)) else {
+            return Err(format!("invalid part {part}"));
+        };
+
+        if let Some(solved) = slot {
+            return Err(format!(
+                "day {day} part {part} was already solved at {} with {}",
+                solved.solved, solved.answer
+            ));
+        }
+
+        Ok(slot.insert(PartEntry {
+            solved: at,
+            answer,
+            runtime: Some(runtime),
+            elapsed: entry.started.map(|started| format_elapsed(started, at)),
+            note: None,
+        }))
+    }
+
+    /// Renders the journal in the layout `notes.txt` was written in by hand.
+    pub fn render_notes(&self) -> String {
+        self.days
+            .iter()
+            .map(render_day)
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn to_document(&self) -> Document {
+        let mut document = Document::default();
+
+        document.table_mut("").set(
+            "utc_offset",
+            Value::String(format_utc_offset(self.utc_offset)),
+        );
+
+        for entry in &self.days {
+            let table = document.table_mut(&format!("day{}", entry.day));
+
+            if let Some(started) = entry.started {
+                table.set("started", Value::String(started.to_string()));
+            }
+
+            for (i, part) in entry.parts.iter().enumerate() {
+                let Some(part) = part else {
+                    continue;
+                };
+
+                let key = |name: &str| format!("part_{}_{name}", i + 1);
+
+                table.set(&key("solved"), Value::String(part.solved.to_string()));
+                table.set(&key("answer"), Value::String(part.answer.clone()));
+                if let Some(runtime) = part.runtime {
+                    let nanos = runtime.as_nanos().min(i64::MAX as u128) as i64;
+                    table.set(&key("runtime_ns"), Value::Integer(nanos));
+                }
+                if let Some(elapsed) = &part.elapsed {
+                    table.set(&key("elapsed"), Value::String(elapsed.clone()));
+                }
+                if let Some(note) = &part.note {
+                    table.set(&key("note"), Value::String(note.clone()));
+                }
+            }
+        }
+
+        document
+    }
+}
+
+impl fmt::Display for Journal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_document())
+    }
+}
+
+fn parse_header(table: &Table) -> Result<i32, String> {
+    let mut utc_offset = 0;
+
+    for (key, value) in &table.entries {
+        match key.as_str() {
+            "utc_offset" => {
+                utc_offset = value.as_str().and_then(parse_utc_offset).ok_or_else(|| {
+                    format!("invalid utc_offset '{value}', expected e.g. '-08:00'")
+                })?
+            }
+            _ => return Err(format!("unknown key '{key}'")),
+        }
+    }
+
+    Ok(utc_offset)
+}
+
+fn parse_day(day: usize, table: &Table) -> Result<DayEntry, String> {
+    let mut entry = DayEntry::new(day);
+
+    let string = |key: &str| -> Result<Option<&str>, String> {
+        table
+            .get(key)
+            .map(|value| {
+                value
+                    .as_str()
+                    .ok_or_else(|| format!("[day{day}] {key} should be a string"))
+            })
+            .transpose()
+    };
+    let timestamp = |key: &str| -> Result<Option<Timestamp>, String> {
+        string(key)?
+            .map(|text| {
+                Timestamp::parse(text).map_err(|error| format!("[day{day}] {key}: {error}"))
+            })
+            .transpose()
+    };
+
+    for (key, _) in &table.entries {
+        let known = key == "started"
+            || ["part_1_", "part_2_"].iter().any(|prefix| {
+                key.strip_prefix(prefix)
+                    .is_some_and(|name| PART_KEYS.contains(&name))
+            });
+
+        if !known {
+            return Err(format!("[day{day}] unknown key '{key}'"));
+        }
+    }
+
+    entry.started = timestamp("started")?;
+
+    for part in [1, 2] {
+        let key = |name: &str| format!("part_{part}_{name}");
+
+        if !PART_KEYS.iter().any(|name| table.get(&key(name)).is_some()) {
+            continue;
+        }
+
+        let (Some(solved), Some(answer)) = (timestamp(&key("solved"))?, table.get(&key("answer")))
+        else {
+            return Err(format!(
+                "[day{day}] part {part} needs both {} and {}",
+                key("solved"),
+                key("answer")
+            ));
+        };
+
+        let runtime = match table.get(&key("runtime_ns")) {
+            Some(value) => Some(
+                value
+                    .as_integer()
+                    .and_then(|nanos| u64::try_from(nanos).ok())
+                    .map(Duration::from_nanos)
+                    .ok_or_else(|| {
+                        format!(
+                            "[day{day}] {} should be a count of nanoseconds",
+                            key("runtime_ns")
+                        )
+                    })?,
+            ),
+            None => None,
+        };
+
+        entry.parts[part - 1] = Some(PartEntry {
+            solved,
+            answer: answer.to_string(),
+            runtime,
+            elapsed: string(&key("elapsed"))?.map(str::to_string),
+            note: string(&key("note"))?.map(str::to_string),
+        });
+    }
+
+    Ok(entry)
+}
+
+fn render_day(entry: &DayEntry) -> String {
+    let mut lines = vec![format!("Day {}:", entry.day)];
+
+    lines.push(match entry.started {
+        Some(started) => format!("    - Started {}", started.to_notes_string()),
+        None => "    - Started".to_string(),
+    });
+
+    for (i, part) in entry.parts.iter().enumerate() {
+        let Some(part) = part else {
+            lines.push(format!("    - part{}:", i + 1));
+            continue;
+        };
+
+        let mut line = format!(
+            "    - part{}: {} [{}",
+            i + 1,
+            part.solved.to_notes_string(),
+            part.answer
+        );
+
+        if let Some(runtime) = part.runtime {
+            // notes.txt was typed with an ASCII micro sign.
+            line.push_str(&format!(", {}", format!("{runtime:?}").replace('µ', "u")));
+        }
+        line.push(']');
+
+        if let Some(elapsed) = &part.elapsed {
+            line.push_str(&format!(" ({elapsed})"));
+        }
+        if let Some(note) = &part.note {
+            line.push_str(&format!(" {}", note.replace('\n', "\n        ")));
+        }
+
+        lines.push(line);
+    }
+
+    lines.join("\n") + "\n"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(text: &str) -> Timestamp {
+        Timestamp::parse(text).unwrap()
+    }
+
+    #[test]
+    fn test_timestamp() {
+        // 2025-12-02 05:00:00 UTC, when day 2 unlocked.
+        let unlock = Timestamp::from_unix(1_764_651_600, -8 * 60);
+
+        assert_eq!(unlock, at("2025-12-01 21:00:00"));
+        assert_eq!(unlock.to_string(), "2025-12-01 21:00:00");
+        assert_eq!(unlock.to_notes_string(), "2025-12-1 9:00pm");
+        assert_eq!(
+            at("2025-12-07 00:04:59").to_notes_string(),
+            "2025-12-7 12:04am"
+        );
+
+        assert!(Timestamp::parse("2025-02-29 10:00:00").is_err());
+        assert!(Timestamp::parse("2025-12-01 9:00pm").is_err());
+        assert!(Timestamp::parse("2025-12-01").is_err());
+    }
+
+    #[test]
+    fn test_format_elapsed() {
+        let started = at("2025-12-01 21:00:00");
+
+        assert_eq!(
+            format_elapsed(started, at("2025-12-01 21:54:11")),
+            "00:54:11"
+        );
+        assert_eq!(
+            format_elapsed(started, at("2025-12-02 20:59:59")),
+            "23:59:59"
+        );
+        assert_eq!(
+            format_elapsed(started, at("2025-12-02 21:00:00")),
+            "> 24 hours"
+        );
+    }
+
+    #[test]
+    fn test_start_and_solve() {
+        let mut journal = Journal::default();
+
+        journal.start_day(12, at("2025-12-11 21:00:00")).unwrap();
+        assert!(journal.start_day(12, at("2025-12-11 22:00:00")).is_err());
+
+        let part = journal
+            .solve_part(
+                12,
+                1,
+                at("2025-12-11 21:20:05"),
+                "42".to_string(),
+                Duration::from_micros(1500),
+            )
+            .unwrap();
+        part.note = Some("First try.\nNo notes.".to_string());
+
+        assert!(
+            journal
+                .solve_part(
+                    12,
+                    1,
+                    at("2025-12-11 21:30:00"),
+                    "43".to_string(),
+                    Duration::ZERO
+                )
+                .is_err()
+        );
+
+        assert_eq!(
+            journal.render_notes(),
+            "Day 12:\n    - Started 2025-12-11 9:00pm\n    - part1: 2025-12-11 9:20pm [42, 1.5ms] (00:20:05) First try.\n        No notes.\n    - part2:\n"
+        );
+
+        let reparsed = Journal::parse(&journal.to_string()).unwrap();
+        assert_eq!(reparsed, journal);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(Journal::parse("[day1]\npart_1_answer = \"5\"\n").is_err());
+        assert!(Journal::parse("[day1]\nstarted = \"yesterday\"\n").is_err());
+        assert!(Journal::parse("[day1]\npart_3_answer = \"5\"\n").is_err());
+        assert!(Journal::parse("[days]\n").is_err());
+        assert!(Journal::parse("utc_offset = \"PST\"\n").is_err());
+    }
+}
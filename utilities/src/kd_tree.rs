@@ -0,0 +1,282 @@
+//! A k-d tree over points with `K` integer coordinates, for finding the points nearest a spot, or
+//! the pairs of points nearest each other, without measuring every pair.
+//!
+//! Distances are squared Euclidean ones. Points the same distance away come in index order, so
+//! results are the same as sorting by `(distance, index)`.
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+pub fn distance_sq<const K: usize>(a: &[i64; K], b: &[i64; K]) -> i64 {
+    a.iter().zip(b).map(|(a, b)| (a - b).pow(2)).sum()
+}
+
+#[derive(Debug, Clone)]
+struct Node<const K: usize> {
+    point: usize,
+    children: [Option<usize>; 2],
+    /// The corners of the box around every point under this node, itself included.
+    min: [i64; K],
+    max: [i64; K],
+}
+
+impl<const K: usize> Node<K> {
+    /// The closest any point under this node could be to `query`.
+    fn lower_bound(&self, query: &[i64; K]) -> i64 {
+        (0..K)
+            .map(|axis| query[axis].clamp(self.min[axis], self.max[axis]))
+            .zip(query)
+            .map(|(closest, query)| (closest - query).pow(2))
+            .sum()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct KdTree<const K: usize> {
+    points: Vec<[i64; K]>,
+    nodes: Vec<Node<K>>,
+    root: Option<usize>,
+}
+
+impl<const K: usize> KdTree<K> {
+    /// Builds a balanced tree, splitting on each axis in turn at the median point.
+    pub fn new(points: &[[i64; K]]) -> Self {
+        let mut tree = Self {
+            points: points.to_vec(),
+            nodes: Vec::with_capacity(points.len()),
+            root: None,
+        };
+
+        let mut indices = (0..points.len()).collect::<Vec<_>>();
+        tree.root = tree.build(&mut indices, 0);
+
+        tree
+    }
+
+    fn build(&mut self, indices: &mut [usize], depth: usize) -> Option<usize> {
+        if indices.is_empty() {
+            return None;
+        }
+
+        let mut min = [i64::MAX; K];
+        let mut max = [i64::MIN; K];
+        for point in indices.iter().map(|index| &self.points[*index]) {
+            for axis in 0..K {
+                min[axis] = min[axis].min(point[axis]);
+                max[axis] = max[axis].max(point[axis]);
+            }
+        }
+
+        let axis = depth % K;
+        let middle = indices.len() / 2;
+        indices.select_nth_unstable_by_key(middle, |index| (self.points[*index][axis], *index));
+
+        let (before, after) = indices.split_at_mut(middle);
+        let (point, after) = after.split_first_mut().unwrap();
+        let point = *point;
+
+        let children = [self.build(before, depth + 1), self.build(after, depth + 1)];
+        self.nodes.push(Node {
+            point,
+            children,
+            min,
+            max,
+        });
+
+        Some(self.nodes.len() - 1)
+    }
+
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    pub fn point(&self, index: usize) -> &[i64; K] {
+        &self.points[index]
+    }
+
+    /// Every point as `(distance², index)`, nearest `query` first. Only as much of the tree is
+    /// searched as it takes to be sure of the next point.
+    pub fn nearest(&self, query: [i64; K]) -> Nearest<'_, K> {
+        let mut queue = BinaryHeap::new();
+
+        if let Some(root) = self.root {
+            queue.push(Reverse((
+                self.nodes[root].lower_bound(&query),
+                Entry::Node(root),
+            )));
+        }
+
+        Nearest {
+            tree: self,
+            query,
+            queue,
+        }
+    }
+
+    pub fn k_nearest(&self, query: [i64; K], k: usize) -> Vec<(i64, usize)> {
+        self.nearest(query).take(k).collect()
+    }
+
+    /// Every pair of points as `(distance², a, b)` with `a < b`, closest first and then by `a`
+    /// and `b`. Each point has its own stream of the points after it nearest first, and the next
+    /// pair is always the closest head of any stream, so pairs are only found as they're needed.
+    pub fn closest_pairs(&self) -> ClosestPairs<'_, K> {
+        let mut pairs = ClosestPairs {
+            streams: self
+                .points
+                .iter()
+                .map(|point| self.nearest(*point))
+                .collect(),
+            heads: BinaryHeap::new(),
+        };
+
+        for a in 0..self.len() {
+            pairs.advance(a);
+        }
+
+        pairs
+    }
+
+    /// The `k` closest pairs, as `closest_pairs` would list them first.
+    pub fn k_closest_pairs(&self, k: usize) -> Vec<(i64, usize, usize)> {
+        self.closest_pairs().take(k).collect()
+    }
+}
+
+/// Nodes sort before points, so a node that could hold a point as near as one already found is
+/// opened first, in case that point has a lower index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Entry {
+    Node(usize),
+    Point(usize),
+}
+
+#[derive(Debug, Clone)]
+pub struct Nearest<'a, const K: usize> {
+    tree: &'a KdTree<K>,
+    query: [i64; K],
+    queue: BinaryHeap<Reverse<(i64, Entry)>>,
+}
+
+impl<const K: usize> Iterator for Nearest<'_, K> {
+    type Item = (i64, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(Reverse((distance, entry))) = self.queue.pop() {
+            let node = match entry {
+                Entry::Point(index) => return Some((distance, index)),
+                Entry::Node(node) => &self.tree.nodes[node],
+            };
+
+            let point = &self.tree.points[node.point];
+            self.queue.push(Reverse((
+                distance_sq(point, &self.query),
+                Entry::Point(node.point),
+            )));
+
+            for child in node.children.iter().flatten() {
+                let bound = self.tree.nodes[*child].lower_bound(&self.query);
+                self.queue.push(Reverse((bound, Entry::Node(*child))));
+            }
+        }
+
+        None
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ClosestPairs<'a, const K: usize> {
+    streams: Vec<Nearest<'a, K>>,
+    heads: BinaryHeap<Reverse<(i64, usize, usize)>>,
+}
+
+impl<const K: usize> ClosestPairs<'_, K> {
+    /// Queues the next pair from `a`'s stream, skipping the points that pair with `a` from their
+    /// own streams instead.
+    fn advance(&mut self, a: usize) {
+        if let Some((distance, b)) = self.streams[a].find(|(_, b)| *b > a) {
+            self.heads.push(Reverse((distance, a, b)));
+        }
+    }
+}
+
+impl<const K: usize> Iterator for ClosestPairs<'_, K> {
+    type Item = (i64, usize, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let Reverse((distance, a, b)) = self.heads.pop()?;
+        self.advance(a);
+
+        Some((distance, a, b))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::rng::Rng;
+
+    /// Points in a small cube, so plenty of them are the same distance apart.
+    fn random_points(seed: u64, len: usize) -> Vec<[i64; 3]> {
+        let mut rng = Rng::new(seed);
+
+        (0..len)
+            .map(|_| [(); 3].map(|_| rng.range(0..=20) as i64))
+            .collect()
+    }
+
+    #[test]
+    fn test_nearest_matches_sorting() {
+        for seed in 0..10 {
+            let points = random_points(seed, 200);
+            let tree = KdTree::new(&points);
+
+            for query in [[0, 0, 0], [10, 10, 10], [25, -3, 7], points[17]] {
+                let mut expected = points
+                    .iter()
+                    .enumerate()
+                    .map(|(index, point)| (distance_sq(point, &query), index))
+                    .collect::<Vec<_>>();
+                expected.sort();
+
+                assert_eq!(tree.nearest(query).collect::<Vec<_>>(), expected);
+                assert_eq!(tree.k_nearest(query, 5), expected[..5]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_closest_pairs_matches_sorting() {
+        for seed in 0..10 {
+            let points = random_points(seed, 120);
+            let tree = KdTree::new(&points);
+
+            let mut expected = vec![];
+            for a in 0..points.len() {
+                for b in (a + 1)..points.len() {
+                    expected.push((distance_sq(&points[a], &points[b]), a, b));
+                }
+            }
+            expected.sort();
+
+            assert_eq!(tree.closest_pairs().collect::<Vec<_>>(), expected);
+            assert_eq!(tree.k_closest_pairs(100), expected[..100]);
+        }
+    }
+
+    #[test]
+    fn test_small_trees() {
+        let tree = KdTree::<2>::new(&[]);
+        assert_eq!(tree.nearest([0, 0]).next(), None);
+        assert_eq!(tree.closest_pairs().next(), None);
+
+        let tree = KdTree::new(&[[5, 5]]);
+        assert_eq!(tree.k_nearest([0, 0], 3), vec![(50, 0)]);
+        assert_eq!(tree.closest_pairs().next(), None);
+    }
+}
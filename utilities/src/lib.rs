@@ -1,46 +1,358 @@
+use std::fmt;
 use std::fs;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::str::FromStr;
 use std::time::Duration;
 use std::time::Instant;
 
-fn instrument<F, T>(f: F, data: &str) -> (T, Duration)
+pub mod bench;
+mod error;
+pub mod fetch;
+pub mod graph;
+pub mod grid;
+pub mod inputs;
+pub mod journal;
+pub mod kd_tree;
+pub mod manifest;
+pub mod parse;
+pub mod pool;
+pub mod profiles;
+pub mod report;
+pub mod rng;
+mod solution;
+pub mod submit;
+pub mod toml;
+pub mod union_find;
+
+pub use error::{PuzzleError, column_of};
+pub use grid::Grid;
+pub use solution::{Answer, ParsedInput, Puzzle, Solution};
+
+use manifest::{ExpectedAnswers, Verdict};
+use report::PartRecord;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Part {
+    One,
+    Two,
+    Both,
+}
+
+impl Part {
+    /// The part numbers this selection covers, in order.
+    pub fn numbers(&self) -> &'static [usize] {
+        match self {
+            Part::One => &[1],
+            Part::Two => &[2],
+            Part::Both => &[1, 2],
+        }
+    }
+}
+
+impl FromStr for Part {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        match text {
+            "1" => Ok(Part::One),
+            "2" => Ok(Part::Two),
+            "both" => Ok(Part::Both),
+            _ => Err(format!("invalid part '{text}', expected 1, 2 or both")),
+        }
+    }
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Part::One => write!(f, "1"),
+            Part::Two => write!(f, "2"),
+            Part::Both => write!(f, "both"),
+        }
+    }
+}
+
+/// Where a run reads its puzzle input from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputSource {
+    /// A file in the day's directory under the input root, checked against `answers.toml`.
+    Data(String),
+    /// Any file, with no recorded answers.
+    Path(PathBuf),
+    Stdin,
+}
+
+impl InputSource {
+    /// Reads the input for `day`, or `None` if it isn't there. Stdin can only be read once.
+    pub fn read(&self, day: usize) -> Option<String> {
+        match self {
+            InputSource::Data(file_name) => read_data(day, file_name),
+            InputSource::Path(path) => fs::read_to_string(path).ok(),
+            InputSource::Stdin => io::read_to_string(io::stdin()).ok(),
+        }
+    }
+
+    /// The name recorded in reports: the file name in `dayN/`, the path, or `-` for stdin.
+    pub fn name(&self) -> String {
+        match self {
+            InputSource::Data(file_name) => file_name.clone(),
+            InputSource::Path(path) => path.display().to_string(),
+            InputSource::Stdin => "-".to_string(),
+        }
+    }
+
+    /// Where the input for `day` is read from, for messages about it.
+    pub fn location(&self, day: usize) -> String {
+        match self {
+            InputSource::Data(file_name) => data_path(day, file_name).display().to_string(),
+            InputSource::Path(path) => path.display().to_string(),
+            InputSource::Stdin => "stdin".to_string(),
+        }
+    }
+
+    fn expected_answers(&self, day: usize) -> Result<Option<ExpectedAnswers>, PuzzleError> {
+        match self {
+            InputSource::Data(file_name) => manifest::expected_answers(day, file_name),
+            _ => Ok(None),
+        }
+    }
+}
+
+impl FromStr for InputSource {
+    type Err = String;
+
+    /// Parses an `--input-path` value, where `-` means stdin.
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        match text {
+            "" => Err("empty input path".to_string()),
+            "-" => Ok(InputSource::Stdin),
+            path => Ok(InputSource::Path(PathBuf::from(path))),
+        }
+    }
+}
+
+/// Selects which parts of a puzzle to run and which input to run them against.
+#[derive(Debug, Clone)]
+pub struct RunOptions {
+    pub part: Part,
+    pub input: InputSource,
+}
+
+impl Default for RunOptions {
+    fn default() -> Self {
+        Self {
+            part: Part::Both,
+            input: InputSource::Data("input.txt".to_string()),
+        }
+    }
+}
+
+fn instrument<F, T>(f: F) -> (T, Duration)
 where
-    F: Fn(&str) -> T,
+    F: FnOnce() -> T,
 {
     let now = Instant::now();
-    let result = f(data);
+    let result = f();
 
     (result, now.elapsed())
 }
 
-fn read_data(day: usize, file_name: &str) -> Option<String> {
-    let file_path = format!("test_files/day{day}/{file_name}");
+/// Where a day's data file lives under the input root, e.g. `test_files/day3/input.txt`.
+pub fn data_path(day: usize, file_name: &str) -> PathBuf {
+    inputs::root().join(format!("day{day}")).join(file_name)
+}
 
-    fs::read_to_string(file_path).ok()
+fn read_data(day: usize, file_name: &str) -> Option<String> {
+    fs::read_to_string(data_path(day, file_name)).ok()
 }
 
 pub fn read_file_data(day: usize, file_name: &str) -> String {
     read_data(day, file_name).unwrap()
 }
 
-pub fn run_puzzle<A, B, F, G>(day: usize, f1: F, f2: G)
-where
-    F: Fn(&str) -> A,
-    G: Fn(&str) -> B,
-    A: std::fmt::Display,
-    B: std::fmt::Display,
-{
-    let file_name = "input.txt";
+/// Reads a personal puzzle input for a test, or reports the test as skipped if it isn't there.
+///
+/// The notice goes straight to stderr rather than through `eprintln!`, so the test harness
+/// doesn't swallow it for passing tests.
+pub fn read_input_or_skip(day: usize, file_name: &str) -> Option<String> {
+    let contents = read_data(day, file_name);
+
+    if contents.is_none() {
+        let thread = std::thread::current();
+        let test_name = thread.name().unwrap_or("test");
 
-    let Some(contents) = read_data(day, file_name) else {
-        println!("Day {day} {file_name} is missing!");
-        return;
+        let _ = writeln!(
+            io::stderr(),
+            "skipped {test_name}: day {day} {file_name} is not available"
+        );
+    }
+
+    contents
+}
+
+/// Evaluates to the contents of a personal puzzle input, returning early from the calling test
+/// if the input is missing. Example inputs should keep using `read_file_data` so they fail hard.
+#[macro_export]
+macro_rules! require_input {
+    ($day:expr, $file_name:expr) => {
+        match $crate::read_input_or_skip($day, $file_name) {
+            Some(contents) => contents,
+            None => return,
+        }
     };
+}
+
+fn format_part(record: &PartRecord) -> String {
+    match &record.answer {
+        Ok(answer) => format!("{answer} ({:?}) [{}]", record.duration, record.verdict),
+        Err(error) => format!("malformed input, {error}"),
+    }
+}
+
+/// A day's input after reading and parsing, shared by the tasks that solve its parts.
+struct PreparedInput<'a> {
+    solution: &'a dyn Solution,
+    source: &'a InputSource,
+    input_hash: u64,
+    expected: Result<Option<ExpectedAnswers>, PuzzleError>,
+    input: Result<ParsedInput, PuzzleError>,
+    parse_duration: Duration,
+}
+
+impl<'a> PreparedInput<'a> {
+    /// Returns `None` if the input file is missing.
+    fn read(solution: &'a dyn Solution, source: &'a InputSource) -> Option<Self> {
+        let day = solution.day();
+        let contents = source.read(day)?;
+
+        let (input, parse_duration) = instrument(|| solution.parse(&contents));
+
+        Some(Self {
+            solution,
+            source,
+            input_hash: report::hash_input(&contents),
+            expected: source.expected_answers(day),
+            input: input.map_err(|e| e.with_day(day)),
+            parse_duration,
+        })
+    }
+
+    fn solve(&self, part: usize) -> PartRecord {
+        let day = self.solution.day();
+
+        let (answer, duration) = match &self.input {
+            Ok(input) => instrument(|| self.solution.solve(input, part)),
+            Err(error) => (Err(error.clone()), Duration::ZERO),
+        };
+
+        let expected = self.expected.as_ref().ok().and_then(Option::as_ref);
+
+        PartRecord {
+            day,
+            part,
+            verdict: match &answer {
+                Ok(answer) => {
+                    Verdict::check(expected.and_then(|expected| expected.part(part)), answer)
+                }
+                Err(_) => Verdict::Unknown,
+            },
+            answer: answer.map_err(|e| e.with_day(day)),
+            duration,
+            parse_duration: self.parse_duration,
+            input_file: self.source.name(),
+            input_hash: self.input_hash,
+        }
+    }
+
+    fn print_summary(&self, records: &[PartRecord]) {
+        let day = self.solution.day();
+
+        if let Err(error) = &self.expected {
+            println!(
+                "[Day {day}]: unable to read {}, {error}",
+                manifest::MANIFEST_FILE
+            );
+        }
+
+        if let Err(error) = &self.input {
+            println!("[Day {day}]: malformed input, {error}");
+            return;
+        }
+
+        let summary = records
+            .iter()
+            .map(|record| format!("part {}: {}", record.part, format_part(record)))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        println!("[Day {day}]: parse ({:?}), {summary}", self.parse_duration);
+    }
+}
+
+/// Runs the selected parts of `solution`, printing a summary line and returning a record per part.
+pub fn run_puzzle(solution: &dyn Solution, options: &RunOptions) -> Vec<PartRecord> {
+    run_puzzles(&[solution], options, 1)
+}
+
+/// Like `run_puzzle` for several solutions, using up to `jobs` threads: every input is parsed
+/// first, then every selected part of every day is solved as its own task. Summary lines are
+/// still printed in day order once everything has finished.
+pub fn run_puzzles(
+    solutions: &[&dyn Solution],
+    options: &RunOptions,
+    jobs: usize,
+) -> Vec<PartRecord> {
+    let inputs = solutions
+        .iter()
+        .map(|solution| (*solution, &options.input))
+        .collect::<Vec<_>>();
+
+    let mut records = vec![];
+
+    for ((solution, source), solved) in
+        inputs
+            .iter()
+            .zip(solve_inputs(&inputs, options.part.numbers(), jobs))
+    {
+        let Some((prepared, day_records)) = solved else {
+            let day = solution.day();
+            println!("Day {day} {} is missing!", source.location(day));
+            continue;
+        };
+
+        prepared.print_summary(&day_records);
+
+        records.extend(day_records);
+    }
+
+    records
+}
+
+/// Reads and parses every `(solution, input)` pair, then solves `parts` of each, all on up to
+/// `jobs` threads. Returns the records in input order, or `None` where the input is missing.
+fn solve_inputs<'a>(
+    inputs: &[(&'a dyn Solution, &'a InputSource)],
+    parts: &[usize],
+    jobs: usize,
+) -> Vec<Option<(PreparedInput<'a>, Vec<PartRecord>)>> {
+    let prepared = pool::map(inputs, jobs, |(solution, source)| {
+        PreparedInput::read(*solution, source)
+    });
+
+    let tasks = prepared
+        .iter()
+        .flatten()
+        .flat_map(|prepared| parts.iter().map(move |part| (prepared, *part)))
+        .collect::<Vec<_>>();
 
-    let part_1 = instrument(f1, &contents);
-    let part_2 = instrument(f2, &contents);
+    let mut solved = pool::map(&tasks, jobs, |(prepared, part)| prepared.solve(*part)).into_iter();
 
-    println!(
-        "[Day {day}]: part 1: {} ({:?}), part 2: {} ({:?})",
-        part_1.0, part_1.1, part_2.0, part_2.1
-    );
+    prepared
+        .into_iter()
+        .map(|prepared| {
+            // Missing files have no tasks, so only take records for the ones that were read.
+            prepared.map(|prepared| (prepared, solved.by_ref().take(parts.len()).collect()))
+        })
+        .collect()
 }
@@ -0,0 +1,209 @@
+//! Expected answers per input file, read from `test_files/dayN/answers.toml`:
+//!
+//! ```toml
+//! ["example.txt"]
+//! part_1 = 3
+//! part_2 = 6
+//! ```
+//!
+//! Personal `input.txt` answers are listed too, so a normal run can tell whether a day still
+//! produces the answer that was accepted, and so are other people's `inputs/<name>.txt` (see
+//! `profiles`).
+
+use std::fmt;
+
+use crate::toml::Document;
+use crate::{Answer, PuzzleError, Solution, read_data, read_input_or_skip};
+
+pub const MANIFEST_FILE: &str = "answers.toml";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExpectedAnswers {
+    pub file_name: String,
+    pub part_1: Option<Answer>,
+    pub part_2: Option<Answer>,
+}
+
+impl ExpectedAnswers {
+    pub fn part(&self, part: usize) -> Option<&Answer> {
+        match part {
+            1 => self.part_1.as_ref(),
+            2 => self.part_2.as_ref(),
+            _ => None,
+        }
+    }
+}
+
+/// How an answer compares to the one recorded in the manifest.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Correct,
+    Wrong { expected: Answer },
+    Unknown,
+}
+
+impl Verdict {
+    pub fn check(expected: Option<&Answer>, actual: &Answer) -> Self {
+        match expected {
+            Some(expected) if expected == actual => Verdict::Correct,
+            Some(expected) => Verdict::Wrong {
+                expected: expected.clone(),
+            },
+            None => Verdict::Unknown,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Verdict::Correct => "correct",
+            Verdict::Wrong { .. } => "wrong",
+            Verdict::Unknown => "unknown",
+        }
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Wrong { expected } => write!(f, "wrong, expected {expected}"),
+            verdict => write!(f, "{}", verdict.name()),
+        }
+    }
+}
+
+/// Reads a day's manifest, or `None` if the day doesn't have one.
+pub fn load_manifest(day: usize) -> Option<Result<Vec<ExpectedAnswers>, PuzzleError>> {
+    let contents = read_data(day, MANIFEST_FILE)?;
+
+    Some(parse_manifest(&contents).map_err(|e| e.with_day(day)))
+}
+
+/// The recorded answers for one input file of a day, if there are any.
+pub fn expected_answers(
+    day: usize,
+    file_name: &str,
+) -> Result<Option<ExpectedAnswers>, PuzzleError> {
+    let Some(manifest) = load_manifest(day) else {
+        return Ok(None);
+    };
+
+    Ok(manifest?
+        .into_iter()
+        .find(|expected| expected.file_name == file_name))
+}
+
+pub fn parse_manifest(contents: &str) -> Result<Vec<ExpectedAnswers>, PuzzleError> {
+    let document = Document::parse(contents)?;
+
+    Ok(document
+        .tables
+        .iter()
+        .filter(|table| !table.name.is_empty())
+        .map(|table| ExpectedAnswers {
+            file_name: table.name.clone(),
+            part_1: table.get("part_1").map(|value| value.to_string()),
+            part_2: table.get("part_2").map(|value| value.to_string()),
+        })
+        .collect())
+}
+
+/// Runs `solution` against every file in its manifest, describing each wrong answer. Personal
+/// inputs aren't committed, so only a missing example counts as a failure.
+pub fn check_manifest(solution: &dyn Solution) -> Vec<String> {
+    let day = solution.day();
+
+    let expected_answers = match load_manifest(day) {
+        Some(Ok(expected_answers)) => expected_answers,
+        Some(Err(error)) => return vec![format!("Day {day} {MANIFEST_FILE}: {error}")],
+        None => return vec![format!("Day {day} {MANIFEST_FILE} is missing")],
+    };
+
+    let mut failures = vec![];
+
+    for expected in expected_answers {
+        let file_name = &expected.file_name;
+
+        let is_example = file_name.starts_with("example");
+
+        let contents = if is_example {
+            read_data(day, file_name)
+        } else {
+            read_input_or_skip(day, file_name)
+        };
+
+        let Some(contents) = contents else {
+            if is_example {
+                failures.push(format!("Day {day} {file_name} is missing"));
+            }
+            continue;
+        };
+
+        for part in [1, 2] {
+            let Some(expected) = expected.part(part) else {
+                continue;
+            };
+
+            let actual = match part {
+                1 => solution.part_1(&contents),
+                _ => solution.part_2(&contents),
+            };
+
+            match actual {
+                Ok(actual) if actual == *expected => {}
+                Ok(actual) => failures.push(format!(
+                    "Day {day} {file_name} part {part}: expected {expected}, got {actual}"
+                )),
+                Err(error) => failures.push(format!(
+                    "Day {day} {file_name} part {part}: {}",
+                    error.with_day(day)
+                )),
+            }
+        }
+    }
+
+    failures
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_manifest() {
+        let manifest = parse_manifest(
+            "[\"example.txt\"]\npart_1 = 3\n\n[\"example2.txt\"]\npart_2 = \"abc\"\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            manifest,
+            vec![
+                ExpectedAnswers {
+                    file_name: "example.txt".to_string(),
+                    part_1: Some("3".to_string()),
+                    part_2: None,
+                },
+                ExpectedAnswers {
+                    file_name: "example2.txt".to_string(),
+                    part_1: None,
+                    part_2: Some("abc".to_string()),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_verdict() {
+        let expected = "170997883706617".to_string();
+
+        assert_eq!(
+            Verdict::check(Some(&expected), &expected.clone()),
+            Verdict::Correct
+        );
+        assert_eq!(
+            Verdict::check(Some(&expected), &"170997883706618".to_string()).to_string(),
+            "wrong, expected 170997883706617"
+        );
+        assert_eq!(Verdict::check(None, &expected), Verdict::Unknown);
+    }
+}
@@ -0,0 +1,417 @@
+//! Small parser combinators for puzzle input.
+//!
+//! A parser is any `Fn(Span) -> ParseResult<T>`. Spans remember their line and column, so every
+//! failure comes back as a `PuzzleError` pointing at the offending character.
+
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+use crate::PuzzleError;
+
+/// The unparsed remainder of some input, along with where it starts. Lines and columns are 1-based.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span<'a> {
+    pub rest: &'a str,
+    pub line: usize,
+    pub column: usize,
+}
+
+pub type ParseResult<'a, T> = Result<(T, Span<'a>), PuzzleError>;
+
+impl<'a> Span<'a> {
+    pub fn new(text: &'a str) -> Self {
+        Self::at_line(text, 1)
+    }
+
+    pub fn at_line(text: &'a str, line: usize) -> Self {
+        Self {
+            rest: text,
+            line,
+            column: 1,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rest.is_empty()
+    }
+
+    pub fn error(&self, message: impl Into<String>) -> PuzzleError {
+        PuzzleError::new(self.line, self.column, message)
+    }
+
+    /// Splits off the first `len` bytes, returning them and the span that follows.
+    fn take(self, len: usize) -> (&'a str, Span<'a>) {
+        let (taken, rest) = self.rest.split_at(len);
+
+        let mut line = self.line;
+        let mut column = self.column;
+        for c in taken.chars() {
+            if c == '\n' {
+                line += 1;
+                column = 1;
+            } else {
+                column += 1;
+            }
+        }
+
+        (taken, Span { rest, line, column })
+    }
+
+    fn describe_next(&self) -> String {
+        match self.rest.chars().next() {
+            Some('\n') => "end of line".to_string(),
+            Some(c) => format!("'{c}'"),
+            None => "end of input".to_string(),
+        }
+    }
+}
+
+/// Runs `parser` over the whole of `text`, allowing only trailing whitespace to be left over.
+pub fn parse_all<'a, T>(
+    text: &'a str,
+    parser: impl Fn(Span<'a>) -> ParseResult<'a, T>,
+) -> Result<T, PuzzleError> {
+    let (value, rest) = parser(Span::new(text))?;
+
+    finish(value, rest)
+}
+
+/// Runs `parser` over every line of `text`, each of which has to be consumed completely.
+pub fn parse_lines<'a, T>(
+    text: &'a str,
+    parser: impl Fn(Span<'a>) -> ParseResult<'a, T>,
+) -> Result<Vec<T>, PuzzleError> {
+    parse_spans(&lines(Span::new(text)), parser)
+}
+
+/// Runs `parser` over every span, e.g. the lines of one of the `sections`.
+pub fn parse_spans<'a, T>(
+    spans: &[Span<'a>],
+    parser: impl Fn(Span<'a>) -> ParseResult<'a, T>,
+) -> Result<Vec<T>, PuzzleError> {
+    spans
+        .iter()
+        .map(|line| {
+            let (value, rest) = parser(*line)?;
+
+            finish(value, rest)
+        })
+        .collect()
+}
+
+fn finish<T>(value: T, rest: Span) -> Result<T, PuzzleError> {
+    let trimmed = rest.rest.trim_start();
+
+    if trimmed.is_empty() {
+        Ok(value)
+    } else {
+        let (_, rest) = rest.take(rest.rest.len() - trimmed.len());
+
+        Err(rest.error(format!("unexpected {}", rest.describe_next())))
+    }
+}
+
+/// Splits a span into its lines, keeping the line numbers of the original input.
+pub fn lines(input: Span) -> Vec<Span> {
+    let mut spans = vec![];
+    let mut input = input;
+
+    while !input.is_empty() {
+        let len = input.rest.find('\n').unwrap_or(input.rest.len());
+        let (line, rest) = input.take(len);
+
+        spans.push(Span {
+            rest: line.strip_suffix('\r').unwrap_or(line),
+            line: input.line,
+            column: input.column,
+        });
+
+        input = if rest.is_empty() {
+            rest
+        } else {
+            rest.take(1).1
+        };
+    }
+
+    spans
+}
+
+/// Splits input into blank-line separated sections.
+pub fn sections(text: &str) -> Vec<Vec<Span<'_>>> {
+    let mut sections = vec![];
+    let mut current = vec![];
+
+    for line in lines(Span::new(text)) {
+        if line.rest.trim().is_empty() {
+            if !current.is_empty() {
+                sections.push(current);
+                current = vec![];
+            }
+        } else {
+            current.push(line);
+        }
+    }
+
+    if !current.is_empty() {
+        sections.push(current);
+    }
+
+    sections
+}
+
+/// Parses each line of `text` into a row of cells, reporting the position of any rejected character.
+pub fn grid<T>(text: &str, cell: impl Fn(char) -> Option<T>) -> Result<Vec<Vec<T>>, PuzzleError> {
+    lines(Span::new(text))
+        .into_iter()
+        .map(|line| {
+            line.rest
+                .chars()
+                .enumerate()
+                .map(|(i, c)| {
+                    cell(c).ok_or_else(|| {
+                        PuzzleError::new(line.line, line.column + i, format!("unexpected '{c}'"))
+                    })
+                })
+                .collect()
+        })
+        .collect()
+}
+
+pub fn tag<'a>(expected: &'static str) -> impl Fn(Span<'a>) -> ParseResult<'a, &'a str> {
+    move |input: Span<'a>| {
+        if input.rest.starts_with(expected) {
+            Ok(input.take(expected.len()))
+        } else {
+            Err(input.error(format!(
+                "expected '{expected}', found {}",
+                input.describe_next()
+            )))
+        }
+    }
+}
+
+/// Consumes the longest prefix whose characters all satisfy `predicate`, which may be empty.
+pub fn take_while<'a>(
+    predicate: impl Fn(char) -> bool,
+) -> impl Fn(Span<'a>) -> ParseResult<'a, &'a str> {
+    move |input: Span<'a>| {
+        let len = input
+            .rest
+            .find(|c| !predicate(c))
+            .unwrap_or(input.rest.len());
+
+        Ok(input.take(len))
+    }
+}
+
+/// Consumes spaces and tabs, but not newlines.
+pub fn spaces(input: Span<'_>) -> ParseResult<'_, &str> {
+    take_while(|c| c == ' ' || c == '\t')(input)
+}
+
+/// Parses an optionally negative decimal integer of any `FromStr` type.
+pub fn integer<'a, T: FromStr>(input: Span<'a>) -> ParseResult<'a, T> {
+    let sign = usize::from(input.rest.starts_with('-'));
+    let digits = input.rest[sign..]
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(input.rest.len() - sign);
+
+    if digits == 0 {
+        return Err(input.error(format!(
+            "expected an integer, found {}",
+            input.describe_next()
+        )));
+    }
+
+    let (text, rest) = input.take(sign + digits);
+
+    text.parse::<T>()
+        .map(|value| (value, rest))
+        .map_err(|_| input.error(format!("integer '{text}' is out of range")))
+}
+
+pub fn map<'a, T, U>(
+    parser: impl Fn(Span<'a>) -> ParseResult<'a, T>,
+    f: impl Fn(T) -> U,
+) -> impl Fn(Span<'a>) -> ParseResult<'a, U> {
+    move |input: Span<'a>| parser(input).map(|(value, rest)| (f(value), rest))
+}
+
+pub fn preceded<'a, T, U>(
+    first: impl Fn(Span<'a>) -> ParseResult<'a, T>,
+    second: impl Fn(Span<'a>) -> ParseResult<'a, U>,
+) -> impl Fn(Span<'a>) -> ParseResult<'a, U> {
+    move |input: Span<'a>| {
+        let (_, input) = first(input)?;
+
+        second(input)
+    }
+}
+
+/// Parses `inner` wrapped between `open` and `close`, e.g. `(1,3)` or `{3,5,4,7}`.
+pub fn delimited<'a, T>(
+    open: &'static str,
+    inner: impl Fn(Span<'a>) -> ParseResult<'a, T>,
+    close: &'static str,
+) -> impl Fn(Span<'a>) -> ParseResult<'a, T> {
+    move |input: Span<'a>| {
+        let (_, input) = tag(open)(input)?;
+        let (value, input) = inner(input)?;
+        let (_, input) = tag(close)(input)?;
+
+        Ok((value, input))
+    }
+}
+
+/// Parses one or more `item`s separated by `separator`.
+pub fn separated<'a, T>(
+    item: impl Fn(Span<'a>) -> ParseResult<'a, T>,
+    separator: &'static str,
+) -> impl Fn(Span<'a>) -> ParseResult<'a, Vec<T>> {
+    move |input: Span<'a>| {
+        let (first, mut input) = item(input)?;
+        let mut items = vec![first];
+
+        while let Ok((_, next)) = tag(separator)(input) {
+            let (value, next) = item(next)?;
+
+            items.push(value);
+            input = next;
+        }
+
+        Ok((items, input))
+    }
+}
+
+/// Parses exactly `N` `item`s separated by `separator`, e.g. the coordinates of a point.
+pub fn separated_array<'a, T, const N: usize>(
+    item: impl Fn(Span<'a>) -> ParseResult<'a, T>,
+    separator: &'static str,
+) -> impl Fn(Span<'a>) -> ParseResult<'a, [T; N]> {
+    let items = separated(item, separator);
+
+    move |input: Span<'a>| {
+        let (values, rest) = items(input)?;
+        let count = values.len();
+
+        values
+            .try_into()
+            .map(|values| (values, rest))
+            .map_err(|_| input.error(format!("expected {N} values, found {count}")))
+    }
+}
+
+/// Parses zero or more `item`s, stopping at the first one that fails without consuming it.
+pub fn many<'a, T>(
+    item: impl Fn(Span<'a>) -> ParseResult<'a, T>,
+) -> impl Fn(Span<'a>) -> ParseResult<'a, Vec<T>> {
+    move |mut input: Span<'a>| {
+        let mut items = vec![];
+
+        while let Ok((value, next)) = item(input) {
+            if next == input {
+                break;
+            }
+
+            items.push(value);
+            input = next;
+        }
+
+        Ok((items, input))
+    }
+}
+
+/// Parses `start<separator>end` into an inclusive range, rejecting ranges that run backwards.
+pub fn range<'a, T: PartialOrd>(
+    bound: impl Fn(Span<'a>) -> ParseResult<'a, T>,
+    separator: &'static str,
+) -> impl Fn(Span<'a>) -> ParseResult<'a, RangeInclusive<T>> {
+    move |input: Span<'a>| {
+        let (start, rest) = bound(input)?;
+        let (_, rest) = tag(separator)(rest)?;
+        let (end, rest) = bound(rest)?;
+
+        if start > end {
+            return Err(input.error("range start is after its end"));
+        }
+
+        Ok((start..=end, rest))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_separated_ranges() {
+        let ranges = parse_all(
+            "11-22,95-115\n",
+            separated(range(integer::<usize>, "-"), ","),
+        );
+
+        assert_eq!(ranges, Ok(vec![11..=22, 95..=115]));
+    }
+
+    #[test]
+    fn test_parse_lines_reports_location() {
+        let result = parse_lines("1,2,3\n4,x,6", separated_array::<isize, 3>(integer, ","));
+
+        assert_eq!(
+            result,
+            Err(PuzzleError::new(2, 3, "expected an integer, found 'x'"))
+        );
+    }
+
+    #[test]
+    fn test_separated_array_count() {
+        let result = parse_all("1,2", separated_array::<usize, 3>(integer, ","));
+
+        assert_eq!(
+            result,
+            Err(PuzzleError::new(1, 1, "expected 3 values, found 2"))
+        );
+    }
+
+    #[test]
+    fn test_delimited_groups() {
+        let group = delimited("(", separated(integer::<usize>, ","), ")");
+        let result = parse_all("(3) (1,3)", many(preceded(spaces, group)));
+
+        assert_eq!(result, Ok(vec![vec![3], vec![1, 3]]));
+    }
+
+    #[test]
+    fn test_trailing_input() {
+        let result = parse_all("12 x", integer::<usize>);
+
+        assert_eq!(result, Err(PuzzleError::new(1, 4, "unexpected 'x'")));
+    }
+
+    #[test]
+    fn test_sections() {
+        let sections = sections("3-5\n10-14\n\n1\n5\n");
+
+        assert_eq!(sections.len(), 2);
+        assert_eq!(sections[0][1].rest, "10-14");
+        assert_eq!(sections[1][0].line, 4);
+    }
+
+    #[test]
+    fn test_grid() {
+        let cell = |c| match c {
+            '.' => Some(false),
+            '@' => Some(true),
+            _ => None,
+        };
+
+        assert_eq!(
+            grid(".@\n@.", cell),
+            Ok(vec![vec![false, true], vec![true, false]])
+        );
+        assert_eq!(
+            grid(".@\n@?", cell),
+            Err(PuzzleError::new(2, 2, "unexpected '?'"))
+        );
+    }
+}
@@ -0,0 +1,96 @@
+//! A fixed number of worker threads pulling independent tasks off a shared counter.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+/// Applies `f` to every item on up to `jobs` threads, returning the results in item order.
+pub fn map<T, R, F>(items: &[T], jobs: usize, f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+{
+    let jobs = jobs.clamp(1, items.len().max(1));
+
+    if jobs == 1 {
+        return items.iter().map(f).collect();
+    }
+
+    let next = AtomicUsize::new(0);
+    let mut results = items.iter().map(|_| None).collect::<Vec<_>>();
+
+    thread::scope(|scope| {
+        let workers = (0..jobs)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut done = vec![];
+
+                    loop {
+                        let index = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(item) = items.get(index) else {
+                            break;
+                        };
+
+                        done.push((index, f(item)));
+                    }
+
+                    done
+                })
+            })
+            .collect::<Vec<_>>();
+
+        for worker in workers {
+            let done = worker
+                .join()
+                .unwrap_or_else(|panic| std::panic::resume_unwind(panic));
+
+            for (index, result) in done {
+                results[index] = Some(result);
+            }
+        }
+    });
+
+    results
+        .into_iter()
+        .map(|result| result.expect("every task was run"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::HashSet;
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    #[test]
+    fn test_map_keeps_order() {
+        let items = (0..50).collect::<Vec<u64>>();
+
+        // Later items finish first, so results arrive out of order.
+        let results = map(&items, 4, |item| {
+            thread::sleep(Duration::from_micros(50 - item));
+            item * 2
+        });
+
+        assert_eq!(results, (0..50).map(|item| item * 2).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_map_uses_several_threads() {
+        let threads = Mutex::new(HashSet::new());
+
+        map(&[(); 16], 4, |_| {
+            threads.lock().unwrap().insert(thread::current().id());
+            thread::sleep(Duration::from_millis(5));
+        });
+
+        assert!(threads.into_inner().unwrap().len() > 1);
+    }
+
+    #[test]
+    fn test_map_empty() {
+        assert_eq!(map(&[] as &[u8], 8, |item| *item), vec![]);
+    }
+}
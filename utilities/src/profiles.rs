@@ -0,0 +1,269 @@
+//! Several people's personal inputs kept side by side, so a day can be checked against all of them.
+//!
+//! Besides `dayN/input.txt` (the `default` profile), each profile has its own
+//! `dayN/inputs/<name>.txt`, with answers listed in the day's `answers.toml` under
+//! `["inputs/<name>.txt"]`.
+
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use crate::manifest::Verdict;
+use crate::report::PartRecord;
+use crate::{InputSource, Part, Solution, data_path, solve_inputs};
+
+pub const PROFILE_DIR: &str = "inputs";
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// The file a profile's input is read from, relative to `dayN/`.
+pub fn profile_file(profile: &str) -> String {
+    if profile == DEFAULT_PROFILE {
+        "input.txt".to_string()
+    } else {
+        format!("{PROFILE_DIR}/{profile}.txt")
+    }
+}
+
+/// The profiles with an input for `day`, `default` first and the rest by name.
+pub fn profiles(day: usize) -> Vec<String> {
+    profiles_in(&data_path(day, ""))
+}
+
+fn profiles_in(day_dir: &Path) -> Vec<String> {
+    let mut names = fs::read_dir(day_dir.join(PROFILE_DIR))
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+
+            match path.extension() {
+                Some(extension) if extension == "txt" => {
+                    Some(path.file_stem()?.to_str()?.to_string())
+                }
+                _ => None,
+            }
+        })
+        .filter(|name| name != DEFAULT_PROFILE)
+        .collect::<Vec<_>>();
+
+    names.sort();
+
+    if day_dir.join(profile_file(DEFAULT_PROFILE)).is_file() {
+        names.insert(0, DEFAULT_PROFILE.to_string());
+    }
+
+    names
+}
+
+/// The result of running every day against every profile.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Matrix {
+    pub profiles: Vec<String>,
+    /// One row per day, with a cell per profile that is `None` if the profile has no input for
+    /// that day.
+    pub rows: Vec<(usize, Vec<Option<Vec<PartRecord>>>)>,
+}
+
+impl Matrix {
+    pub fn records(&self) -> impl Iterator<Item = &PartRecord> {
+        self.rows
+            .iter()
+            .flat_map(|(_, cells)| cells.iter().flatten().flatten())
+    }
+
+    /// Describes every answer that doesn't match its profile's `answers.toml`.
+    pub fn wrong_answers(&self) -> Vec<String> {
+        self.records()
+            .filter(|record| matches!(record.verdict, Verdict::Wrong { .. }))
+            .map(|record| {
+                format!(
+                    "Day {} {} part {}: {}",
+                    record.day, record.input_file, record.part, record.verdict
+                )
+            })
+            .collect()
+    }
+}
+
+/// Runs the selected parts of every solution against every profile's input on up to `jobs`
+/// threads.
+pub fn run_matrix(solutions: &[&dyn Solution], part: Part, jobs: usize) -> Matrix {
+    let mut profiles = vec![];
+
+    for solution in solutions {
+        for profile in self::profiles(solution.day()) {
+            if !profiles.contains(&profile) {
+                profiles.push(profile);
+            }
+        }
+    }
+
+    // Keep `default` first and the rest by name, whichever day they first turned up on.
+    profiles.sort_by_key(|profile| (profile != DEFAULT_PROFILE, profile.clone()));
+
+    let sources = profiles
+        .iter()
+        .map(|profile| InputSource::Data(profile_file(profile)))
+        .collect::<Vec<_>>();
+
+    let inputs = solutions
+        .iter()
+        .flat_map(|solution| sources.iter().map(move |source| (*solution, source)))
+        .collect::<Vec<_>>();
+
+    let mut solved = solve_inputs(&inputs, part.numbers(), jobs)
+        .into_iter()
+        .map(|solved| solved.map(|(_, records)| records));
+
+    let rows = solutions
+        .iter()
+        .map(|solution| {
+            let cells = solved.by_ref().take(sources.len()).collect();
+
+            (solution.day(), cells)
+        })
+        .collect();
+
+    Matrix { profiles, rows }
+}
+
+fn format_cell(cell: &Option<Vec<PartRecord>>) -> String {
+    let Some(records) = cell else {
+        return "-".to_string();
+    };
+
+    records
+        .iter()
+        .map(|record| match (&record.answer, &record.verdict) {
+            (Err(_), _) => "error",
+            (Ok(_), Verdict::Correct) => "ok",
+            (Ok(_), Verdict::Wrong { .. }) => "WRONG",
+            (Ok(_), Verdict::Unknown) => "?",
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// A table with a row per day and a column per profile, where each cell shows `ok`, `WRONG`, `?`
+/// (no recorded answer) or `error` for each part, and `-` if the profile has no input.
+impl fmt::Display for Matrix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let header = std::iter::once("Day".to_string())
+            .chain(self.profiles.iter().cloned())
+            .collect::<Vec<_>>();
+
+        let lines = self
+            .rows
+            .iter()
+            .map(|(day, cells)| {
+                std::iter::once(day.to_string())
+                    .chain(cells.iter().map(format_cell))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        let widths = (0..header.len())
+            .map(|column| {
+                std::iter::once(&header)
+                    .chain(&lines)
+                    .map(|line| line[column].len())
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect::<Vec<_>>();
+
+        for line in std::iter::once(&header).chain(&lines) {
+            let cells = line
+                .iter()
+                .zip(&widths)
+                .map(|(cell, width)| format!("{cell:width$}"))
+                .collect::<Vec<_>>();
+
+            writeln!(f, "{}", cells.join("  ").trim_end())?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::time::Duration;
+
+    use crate::fetch::tests::temp_dir;
+
+    fn record(part: usize, verdict: Verdict) -> PartRecord {
+        PartRecord {
+            day: 8,
+            part,
+            answer: Ok("42".to_string()),
+            verdict,
+            duration: Duration::ZERO,
+            parse_duration: Duration::ZERO,
+            input_file: profile_file("alice"),
+            input_hash: 0,
+        }
+    }
+
+    #[test]
+    fn test_profile_file() {
+        assert_eq!(profile_file("default"), "input.txt");
+        assert_eq!(profile_file("alice"), "inputs/alice.txt");
+    }
+
+    #[test]
+    fn test_profiles_in() {
+        let dir = temp_dir("profiles");
+        fs::create_dir_all(dir.join(PROFILE_DIR)).unwrap();
+
+        assert_eq!(profiles_in(&dir), Vec::<String>::new());
+
+        for file in [
+            "input.txt",
+            "inputs/bob.txt",
+            "inputs/alice.txt",
+            "inputs/notes.md",
+        ] {
+            fs::write(dir.join(file), "").unwrap();
+        }
+
+        assert_eq!(profiles_in(&dir), vec!["default", "alice", "bob"]);
+    }
+
+    #[test]
+    fn test_matrix_display() {
+        let wrong = Verdict::Wrong {
+            expected: "7".to_string(),
+        };
+        let matrix = Matrix {
+            profiles: vec!["default".to_string(), "alice".to_string()],
+            rows: vec![
+                (
+                    8,
+                    vec![
+                        Some(vec![
+                            record(1, Verdict::Correct),
+                            record(2, Verdict::Correct),
+                        ]),
+                        Some(vec![record(1, Verdict::Unknown), record(2, wrong)]),
+                    ],
+                ),
+                (11, vec![Some(vec![record(1, Verdict::Correct)]), None]),
+            ],
+        };
+
+        assert_eq!(
+            matrix.to_string(),
+            "Day  default  alice\n\
+             8    ok ok    ? WRONG\n\
+             11   ok       -\n"
+        );
+        assert_eq!(
+            matrix.wrong_answers(),
+            vec!["Day 8 inputs/alice.txt part 2: wrong, expected 7"]
+        );
+    }
+}
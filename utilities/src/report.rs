@@ -0,0 +1,250 @@
+//! Machine-readable records of puzzle runs, written as JSON lines or CSV.
+
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::manifest::Verdict;
+use crate::{Answer, PuzzleError};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    JsonLines,
+    Csv,
+}
+
+impl ReportFormat {
+    pub fn default_file_name(&self) -> &'static str {
+        match self {
+            ReportFormat::JsonLines => "run_report.jsonl",
+            ReportFormat::Csv => "run_report.csv",
+        }
+    }
+}
+
+impl FromStr for ReportFormat {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        match text {
+            "json" | "jsonl" => Ok(ReportFormat::JsonLines),
+            "csv" => Ok(ReportFormat::Csv),
+            _ => Err(format!(
+                "invalid report format '{text}', expected json or csv"
+            )),
+        }
+    }
+}
+
+impl fmt::Display for ReportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReportFormat::JsonLines => write!(f, "json"),
+            ReportFormat::Csv => write!(f, "csv"),
+        }
+    }
+}
+
+/// The outcome of running one part of one day against one input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartRecord {
+    pub day: usize,
+    pub part: usize,
+    pub answer: Result<Answer, PuzzleError>,
+    pub verdict: Verdict,
+    pub duration: Duration,
+    pub parse_duration: Duration,
+    pub input_file: String,
+    pub input_hash: u64,
+}
+
+const CSV_HEADER: &str = "day,part,answer,error,verdict,duration_ns,parse_ns,input_file,input_hash";
+
+pub struct ReportWriter {
+    format: ReportFormat,
+    output: Box<dyn Write>,
+}
+
+impl ReportWriter {
+    pub fn new(format: ReportFormat, output: Box<dyn Write>) -> io::Result<Self> {
+        let mut writer = Self { format, output };
+
+        if format == ReportFormat::Csv {
+            writeln!(writer.output, "{CSV_HEADER}")?;
+        }
+
+        Ok(writer)
+    }
+
+    pub fn create(format: ReportFormat, path: &Path) -> io::Result<Self> {
+        let file = File::create(path)?;
+
+        Self::new(format, Box::new(BufWriter::new(file)))
+    }
+
+    pub fn write_record(&mut self, record: &PartRecord) -> io::Result<()> {
+        let (answer, error) = match &record.answer {
+            Ok(answer) => (Some(answer.clone()), None),
+            Err(error) => (None, Some(error.to_string())),
+        };
+        let hash = format_hash(record.input_hash);
+
+        match self.format {
+            ReportFormat::JsonLines => writeln!(
+                self.output,
+                "{{\"day\":{},\"part\":{},\"answer\":{},\"error\":{},\"verdict\":{},\"duration_ns\":{},\"parse_ns\":{},\"input_file\":{},\"input_hash\":{}}}",
+                record.day,
+                record.part,
+                json_option(answer.as_deref()),
+                json_option(error.as_deref()),
+                json_string(record.verdict.name()),
+                record.duration.as_nanos(),
+                record.parse_duration.as_nanos(),
+                json_string(&record.input_file),
+                json_string(&hash),
+            ),
+            ReportFormat::Csv => writeln!(
+                self.output,
+                "{},{},{},{},{},{},{},{},{}",
+                record.day,
+                record.part,
+                csv_field(answer.as_deref().unwrap_or("")),
+                csv_field(error.as_deref().unwrap_or("")),
+                record.verdict.name(),
+                record.duration.as_nanos(),
+                record.parse_duration.as_nanos(),
+                csv_field(&record.input_file),
+                hash,
+            ),
+        }
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.output.flush()
+    }
+}
+
+/// 64-bit FNV-1a, which unlike `DefaultHasher` is stable across Rust versions and machines.
+pub fn hash_input(contents: &str) -> u64 {
+    contents.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+pub fn format_hash(hash: u64) -> String {
+    format!("{hash:016x}")
+}
+
+pub(crate) fn json_string(text: &str) -> String {
+    let mut result = String::with_capacity(text.len() + 2);
+    result.push('"');
+
+    for c in text.chars() {
+        match c {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            '\t' => result.push_str("\\t"),
+            c if (c as u32) < 0x20 => result.push_str(&format!("\\u{:04x}", c as u32)),
+            c => result.push(c),
+        }
+    }
+
+    result.push('"');
+    result
+}
+
+fn json_option(text: Option<&str>) -> String {
+    text.map_or("null".to_string(), json_string)
+}
+
+pub(crate) fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Rc<RefCell<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.borrow_mut().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn write_records(format: ReportFormat) -> String {
+        let buffer = SharedBuffer::default();
+        let mut writer = ReportWriter::new(format, Box::new(buffer.clone())).unwrap();
+
+        let records = [
+            PartRecord {
+                day: 3,
+                part: 1,
+                answer: Ok("357".to_string()),
+                verdict: Verdict::Correct,
+                duration: Duration::from_micros(18),
+                parse_duration: Duration::from_micros(2),
+                input_file: "example.txt".to_string(),
+                input_hash: hash_input(""),
+            },
+            PartRecord {
+                day: 1,
+                part: 2,
+                answer: Err(PuzzleError::new(3, 1, "unknown direction 'Q'").with_day(1)),
+                verdict: Verdict::Unknown,
+                duration: Duration::from_nanos(5),
+                parse_duration: Duration::ZERO,
+                input_file: "bad, \"file\".txt".to_string(),
+                input_hash: 1,
+            },
+        ];
+
+        for record in &records {
+            writer.write_record(record).unwrap();
+        }
+
+        String::from_utf8(buffer.0.borrow().clone()).unwrap()
+    }
+
+    #[test]
+    fn test_json_lines() {
+        assert_eq!(
+            write_records(ReportFormat::JsonLines),
+            "{\"day\":3,\"part\":1,\"answer\":\"357\",\"error\":null,\"verdict\":\"correct\",\"duration_ns\":18000,\"parse_ns\":2000,\"input_file\":\"example.txt\",\"input_hash\":\"cbf29ce484222325\"}\n\
+             {\"day\":1,\"part\":2,\"answer\":null,\"error\":\"day 1, line 3, column 1: unknown direction 'Q'\",\"verdict\":\"unknown\",\"duration_ns\":5,\"parse_ns\":0,\"input_file\":\"bad, \\\"file\\\".txt\",\"input_hash\":\"0000000000000001\"}\n"
+        );
+    }
+
+    #[test]
+    fn test_csv() {
+        assert_eq!(
+            write_records(ReportFormat::Csv),
+            "day,part,answer,error,verdict,duration_ns,parse_ns,input_file,input_hash\n\
+             3,1,357,,correct,18000,2000,example.txt,cbf29ce484222325\n\
+             1,2,,\"day 1, line 3, column 1: unknown direction 'Q'\",unknown,5,0,\"bad, \"\"file\"\".txt\",0000000000000001\n"
+        );
+    }
+
+    #[test]
+    fn test_hash_input() {
+        assert_eq!(hash_input("a"), 0xaf63dc4c8601ec8c);
+    }
+}
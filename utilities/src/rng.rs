@@ -0,0 +1,181 @@
+//! A small seeded pseudo-random number generator (SplitMix64), so generated puzzle inputs can be
+//! reproduced from their seed.
+
+use std::fmt::Debug;
+use std::ops::{Range, RangeInclusive};
+use std::panic::{self, AssertUnwindSafe};
+
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+
+        z ^ (z >> 31)
+    }
+
+    /// A uniform value in `range`.
+    pub fn range(&mut self, range: RangeInclusive<usize>) -> usize {
+        let (start, end) = range.into_inner();
+        assert!(start <= end, "empty range {start}..={end}");
+
+        let span = ((end - start) as u64).wrapping_add(1);
+        if span == 0 {
+            // The range covers every u64.
+            return self.next_u64() as usize;
+        }
+
+        // Reject the top of the range that doesn't divide evenly, so every value is as likely.
+        let zone = u64::MAX - (u64::MAX - span + 1) % span;
+        loop {
+            let value = self.next_u64();
+            if value <= zone {
+                return start + (value % span) as usize;
+            }
+        }
+    }
+
+    /// A uniform index below `len`.
+    pub fn below(&mut self, len: usize) -> usize {
+        assert!(len > 0, "no values below 0");
+
+        self.range(0..=len - 1)
+    }
+
+    /// True with the given probability.
+    pub fn chance(&mut self, probability: f64) -> bool {
+        ((self.next_u64() >> 11) as f64 / (1u64 << 53) as f64) < probability
+    }
+
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len())]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+}
+
+/// Generates an input of `size` from each of `seeds`, parses it and hands both to `check`, which
+/// compares the solver against an oracle. A failing check is reported with its seed, so the input
+/// can be reproduced with `generate DAY --seed SEED --size SIZE`.
+pub fn for_each_generated<T, E: Debug>(
+    seeds: Range<u64>,
+    size: usize,
+    generate: impl Fn(&mut Rng, usize) -> String,
+    parse: impl Fn(&str) -> Result<T, E>,
+    mut check: impl FnMut(&str, T),
+) {
+    for seed in seeds {
+        let contents = generate(&mut Rng::new(seed), size);
+        let input = parse(&contents).unwrap_or_else(|error| {
+            panic!("the input generated from seed {seed} doesn't parse: {error:?}")
+        });
+
+        if let Err(panic) = panic::catch_unwind(AssertUnwindSafe(|| check(&contents, input))) {
+            eprintln!("check failed on the input generated from seed {seed} with size {size}");
+            panic::resume_unwind(panic);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_same_seed_same_values() {
+        let mut a = Rng::new(2025);
+        let mut b = Rng::new(2025);
+
+        let a_values = (0..10).map(|_| a.next_u64()).collect::<Vec<_>>();
+        let b_values = (0..10).map(|_| b.next_u64()).collect::<Vec<_>>();
+
+        assert_eq!(a_values, b_values);
+        assert_ne!(Rng::new(2026).next_u64(), a_values[0]);
+    }
+
+    #[test]
+    fn test_range_bounds() {
+        let mut rng = Rng::new(7);
+        let mut seen = [false; 6];
+
+        for _ in 0..1000 {
+            let value = rng.range(10..=15);
+
+            assert!((10..=15).contains(&value));
+            seen[value - 10] = true;
+        }
+
+        assert!(seen.iter().all(|seen| *seen));
+        assert_eq!(rng.range(4..=4), 4);
+        assert_ne!(rng.range(0..=usize::MAX), rng.range(0..=usize::MAX));
+    }
+
+    #[test]
+    fn test_chance() {
+        let mut rng = Rng::new(1);
+
+        let hits = (0..10_000).filter(|_| rng.chance(0.25)).count();
+
+        assert!((2_200..2_800).contains(&hits), "{hits}");
+        assert!(!rng.chance(0.0));
+        assert!(rng.chance(1.0));
+    }
+
+    #[test]
+    fn test_shuffle_is_a_permutation() {
+        let mut rng = Rng::new(3);
+        let mut items = (0..50).collect::<Vec<_>>();
+
+        rng.shuffle(&mut items);
+
+        assert_ne!(items, (0..50).collect::<Vec<_>>());
+        items.sort();
+        assert_eq!(items, (0..50).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_for_each_generated() {
+        let mut seen = vec![];
+
+        for_each_generated(
+            3..6,
+            4,
+            |rng, size| (0..size).map(|_| rng.below(10).to_string()).collect(),
+            |contents| contents.parse::<u64>(),
+            |contents, number| seen.push((contents.len(), number)),
+        );
+
+        assert_eq!(seen.len(), 3);
+        assert!(
+            seen.iter()
+                .all(|(len, number)| *len == 4 && *number < 10_000)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "seed 0 doesn't parse")]
+    fn test_for_each_generated_unparsable() {
+        for_each_generated(
+            0..1,
+            1,
+            |_, _| "x".to_string(),
+            |contents| contents.parse::<u64>(),
+            |_, _| {},
+        );
+    }
+}
@@ -0,0 +1,128 @@
+use std::any::Any;
+
+use crate::PuzzleError;
+use crate::rng::Rng;
+
+pub type Answer = String;
+
+/// Parsed input with its concrete type erased, so the runner can time parsing on its own and
+/// share the result between both parts.
+pub type ParsedInput = Box<dyn Any + Send + Sync>;
+
+/// A single day's puzzle. Every day module implements this and is listed in the binary's registry.
+pub trait Puzzle: Sync {
+    type Input: Any + Send + Sync;
+
+    fn day(&self) -> usize;
+
+    fn title(&self) -> &'static str;
+
+    fn parse(&self, contents: &str) -> Result<Self::Input, PuzzleError>;
+
+    fn part_1(&self, input: &Self::Input) -> Result<Answer, PuzzleError>;
+
+    fn part_2(&self, input: &Self::Input) -> Result<Answer, PuzzleError>;
+
+    /// A random, valid input of roughly `size` items, or `None` if the day has no generator.
+    fn generate(&self, _rng: &mut Rng, _size: usize) -> Option<String> {
+        None
+    }
+}
+
+/// The object-safe view of a `Puzzle` that the registry and runners work with.
+pub trait Solution: Sync {
+    fn day(&self) -> usize;
+
+    fn title(&self) -> &'static str;
+
+    fn parse(&self, contents: &str) -> Result<ParsedInput, PuzzleError>;
+
+    /// Solves `part` (1 or 2) of an input returned by this solution's `parse`.
+    fn solve(&self, input: &ParsedInput, part: usize) -> Result<Answer, PuzzleError>;
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> Option<String>;
+
+    fn part_1(&self, contents: &str) -> Result<Answer, PuzzleError> {
+        self.solve(&self.parse(contents)?, 1)
+    }
+
+    fn part_2(&self, contents: &str) -> Result<Answer, PuzzleError> {
+        self.solve(&self.parse(contents)?, 2)
+    }
+}
+
+impl<P: Puzzle> Solution for P {
+    fn day(&self) -> usize {
+        Puzzle::day(self)
+    }
+
+    fn title(&self) -> &'static str {
+        Puzzle::title(self)
+    }
+
+    fn parse(&self, contents: &str) -> Result<ParsedInput, PuzzleError> {
+        Ok(Box::new(Puzzle::parse(self, contents)?))
+    }
+
+    fn solve(&self, input: &ParsedInput, part: usize) -> Result<Answer, PuzzleError> {
+        let input = input
+            .downcast_ref::<P::Input>()
+            .expect("input was parsed by a different solution");
+
+        match part {
+            1 => Puzzle::part_1(self, input),
+            2 => Puzzle::part_2(self, input),
+            _ => panic!("invalid part {part}"),
+        }
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> Option<String> {
+        Puzzle::generate(self, rng, size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Sum;
+
+    impl Puzzle for Sum {
+        type Input = Vec<usize>;
+
+        fn day(&self) -> usize {
+            0
+        }
+
+        fn title(&self) -> &'static str {
+            "Sum"
+        }
+
+        fn parse(&self, contents: &str) -> Result<Vec<usize>, PuzzleError> {
+            crate::parse::parse_lines(contents, crate::parse::integer)
+        }
+
+        fn part_1(&self, input: &Vec<usize>) -> Result<Answer, PuzzleError> {
+            Ok(input.iter().sum::<usize>().to_string())
+        }
+
+        fn part_2(&self, input: &Vec<usize>) -> Result<Answer, PuzzleError> {
+            Ok(input.iter().product::<usize>().to_string())
+        }
+    }
+
+    #[test]
+    fn test_erased_solution() {
+        let solution: &dyn Solution = &Sum;
+
+        let input = solution.parse("2\n3\n4").unwrap();
+
+        assert_eq!(solution.solve(&input, 1), Ok("9".to_string()));
+        assert_eq!(solution.solve(&input, 2), Ok("24".to_string()));
+        assert_eq!(solution.part_1("1\n1"), Ok("2".to_string()));
+        assert_eq!(
+            solution.part_2("1\nx"),
+            Err(PuzzleError::new(2, 1, "expected an integer, found 'x'"))
+        );
+    }
+}
@@ -0,0 +1,465 @@
+//! Posting answers to the site, and remembering what was sent so a wrong guess, or one already
+//! ruled out by a "too high" or "too low", never costs another cooldown.
+
+use std::fmt;
+use std::time::Duration;
+
+use crate::PuzzleError;
+use crate::fetch::{Client, FetchError};
+use crate::toml::{Document, Value};
+
+pub const LOG_FILE: &str = "submissions.toml";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Correct,
+    TooHigh,
+    TooLow,
+    /// Wrong, without a hint about which way.
+    Wrong,
+    /// Submitted too soon after the previous answer; nothing was checked.
+    Wait,
+    /// The part was already solved, or isn't unlocked yet.
+    AlreadySolved,
+}
+
+impl Outcome {
+    pub fn name(self) -> &'static str {
+        match self {
+            Outcome::Correct => "correct",
+            Outcome::TooHigh => "too_high",
+            Outcome::TooLow => "too_low",
+            Outcome::Wrong => "wrong",
+            Outcome::Wait => "wait",
+            Outcome::AlreadySolved => "already_solved",
+        }
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Outcome::Correct => write!(f, "correct"),
+            Outcome::TooHigh => write!(f, "wrong, too high"),
+            Outcome::TooLow => write!(f, "wrong, too low"),
+            Outcome::Wrong => write!(f, "wrong"),
+            Outcome::Wait => write!(f, "submitted too recently"),
+            Outcome::AlreadySolved => write!(f, "already solved or not unlocked"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reply {
+    pub outcome: Outcome,
+    /// How long the site wants before the next answer.
+    pub cooldown: Option<Duration>,
+}
+
+/// Reads the outcome out of the page the site returns for a submitted answer.
+pub fn parse_reply(body: &str) -> Option<Reply> {
+    let text = body.to_lowercase();
+
+    let outcome = if text.contains("that's the right answer") {
+        Outcome::Correct
+    } else if text.contains("that's not the right answer") {
+        if text.contains("too high") {
+            Outcome::TooHigh
+        } else if text.contains("too low") {
+            Outcome::TooLow
+        } else {
+            Outcome::Wrong
+        }
+    } else if text.contains("you gave an answer too recently") {
+        Outcome::Wait
+    } else if text.contains("you don't seem to be solving the right level") {
+        Outcome::AlreadySolved
+    } else {
+        return None;
+    };
+
+    let cooldown = left_to_wait(&text).or_else(|| wait_before_trying_again(&text));
+
+    Some(Reply { outcome, cooldown })
+}
+
+/// Parses "you have 4m 40s left to wait".
+fn left_to_wait(text: &str) -> Option<Duration> {
+    let end = text.find(" left to wait")?;
+    let start = text[..end].rfind("you have ")? + "you have ".len();
+
+    let mut seconds = 0;
+    for piece in text[start..end].split_whitespace() {
+        let (number, unit) = piece.split_at(piece.find(|c: char| !c.is_ascii_digit())?);
+        let number = number.parse::<u64>().ok()?;
+
+        seconds += match unit {
+            "h" => number * 3600,
+            "m" => number * 60,
+            "s" => number,
+            _ => return None,
+        };
+    }
+
+    Some(Duration::from_secs(seconds))
+}
+
+/// Parses "please wait one minute before trying again" and "wait 5 minutes before trying again".
+fn wait_before_trying_again(text: &str) -> Option<Duration> {
+    let end = text.find(" before trying again")?;
+    let start = text[..end].rfind("wait ")? + "wait ".len();
+    let (count, unit) = text[start..end].split_once(' ')?;
+
+    let count = match count {
+        "one" | "a" | "an" => 1,
+        count => count.parse::<u64>().ok()?,
+    };
+
+    match unit.trim_end_matches('s') {
+        "minute" => Some(Duration::from_secs(count * 60)),
+        "second" => Some(Duration::from_secs(count)),
+        _ => None,
+    }
+}
+
+/// Every answer sent so far, one table per part, plus when the site will next accept one.
+///
+/// ```toml
+/// next_submission = 1764651660
+///
+/// [day3_part_2]
+/// 1234 = "too_low"
+/// 9876 = "too_high"
+/// ```
+#[derive(Debug, Default)]
+pub struct GuessLog {
+    document: Document,
+}
+
+impl GuessLog {
+    pub fn parse(text: &str) -> Result<Self, PuzzleError> {
+        Ok(Self {
+            document: Document::parse(text)?,
+        })
+    }
+
+    fn table_name(day: usize, part: usize) -> String {
+        format!("day{day}_part_{part}")
+    }
+
+    /// The answers already sent for a part, with the name of their outcome.
+    pub fn guesses(&self, day: usize, part: usize) -> Vec<(&str, &str)> {
+        self.document
+            .table(&Self::table_name(day, part))
+            .map(|table| {
+                table
+                    .entries
+                    .iter()
+                    .filter_map(|(answer, outcome)| Some((answer.as_str(), outcome.as_str()?)))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// The unix time before which the site won't look at another answer.
+    pub fn next_submission(&self) -> Option<i64> {
+        self.document
+            .table("")?
+            .get("next_submission")?
+            .as_integer()
+    }
+
+    /// Explains why `answer` shouldn't be sent at unix time `now`, or returns `Ok` if it may be.
+    pub fn check(&self, day: usize, part: usize, answer: &str, now: i64) -> Result<(), String> {
+        if let Some(next) = self.next_submission().filter(|next| *next > now) {
+            return Err(format!(
+                "the site won't accept another answer for {}s",
+                next - now
+            ));
+        }
+
+        let guesses = self.guesses(day, part);
+
+        if let Some((correct, _)) = guesses
+            .iter()
+            .find(|(_, outcome)| *outcome == Outcome::Correct.name())
+        {
+            return Err(format!("already solved with {correct}"));
+        }
+
+        if let Some((_, outcome)) = guesses.iter().find(|(guess, _)| *guess == answer) {
+            return Err(format!("{answer} was already sent ({outcome})"));
+        }
+
+        let Ok(value) = answer.parse::<i128>() else {
+            return Ok(());
+        };
+
+        for (guess, outcome) in guesses {
+            let Ok(guess_value) = guess.parse::<i128>() else {
+                continue;
+            };
+
+            if outcome == Outcome::TooHigh.name() && value >= guess_value {
+                return Err(format!("{answer} is not below {guess}, which was too high"));
+            }
+
+            if outcome == Outcome::TooLow.name() && value <= guess_value {
+                return Err(format!("{answer} is not above {guess}, which was too low"));
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn record(&mut self, day: usize, part: usize, answer: &str, reply: &Reply, now: i64) {
+        if let Outcome::Correct | Outcome::TooHigh | Outcome::TooLow | Outcome::Wrong =
+            reply.outcome
+        {
+            self.document
+                .table_mut(&Self::table_name(day, part))
+                .set(answer, Value::String(reply.outcome.name().to_string()));
+        }
+
+        if let Some(cooldown) = reply.cooldown {
+            let next = now + cooldown.as_secs() as i64;
+
+            self.document
+                .table_mut("")
+                .set("next_submission", Value::Integer(next));
+        }
+    }
+}
+
+impl fmt::Display for GuessLog {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.document)
+    }
+}
+
+#[derive(Debug)]
+pub enum SubmitError {
+    /// The log shows the answer can't be right, or the site is still cooling down.
+    Refused(String),
+    Request(FetchError),
+    /// The site answered, but not with a page we know how to read.
+    Unrecognised(String),
+}
+
+impl fmt::Display for SubmitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SubmitError::Refused(reason) => write!(f, "not submitted: {reason}"),
+            SubmitError::Request(error) => write!(f, "{error}"),
+            SubmitError::Unrecognised(body) => write!(f, "unrecognised reply: {body}"),
+        }
+    }
+}
+
+impl std::error::Error for SubmitError {}
+
+impl From<FetchError> for SubmitError {
+    fn from(error: FetchError) -> Self {
+        SubmitError::Request(error)
+    }
+}
+
+/// Sends `answer` for a part unless `log` already rules it out, then records the outcome in `log`.
+pub fn submit(
+    client: &Client,
+    log: &mut GuessLog,
+    day: usize,
+    part: usize,
+    answer: &str,
+    now: i64,
+) -> Result<Reply, SubmitError> {
+    let answer = answer.trim();
+
+    log.check(day, part, answer, now)
+        .map_err(SubmitError::Refused)?;
+
+    let body = format!("level={part}&answer={}", form_encode(answer));
+    let request = client.post_form(&format!("/day/{day}/answer"), body);
+
+    let response = client.send(&request).map_err(FetchError::from)?;
+
+    if response.status != 200 {
+        return Err(FetchError::Status {
+            status: response.status,
+            message: response.body.trim().to_string(),
+        }
+        .into());
+    }
+
+    let reply = parse_reply(&response.body).ok_or_else(|| {
+        SubmitError::Unrecognised(response.body.chars().take(200).collect::<String>())
+    })?;
+
+    log.record(day, part, answer, &reply, now);
+
+    Ok(reply)
+}
+
+fn form_encode(text: &str) -> String {
+    text.bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (byte as char).to_string()
+            }
+            b' ' => "+".to_string(),
+            byte => format!("%{byte:02X}"),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::fetch::tests::{StandIn, client, response};
+
+    const RIGHT: &str = "<article><p>That's the right answer! You are one gold star closer to \
+                         decorating the North Pole.</p></article>";
+    const TOO_HIGH: &str = "<article><p>That's not the right answer; your answer is too high. \
+                            If you're stuck, make sure you're using the full input data. Please \
+                            wait one minute before trying again.</p></article>";
+    const TOO_RECENT: &str = "<article><p>You gave an answer too recently; you have to wait \
+                              after submitting an answer before trying again.  You have 4m 40s \
+                              left to wait.</p></article>";
+
+    #[test]
+    fn test_parse_reply() {
+        assert_eq!(
+            parse_reply(RIGHT),
+            Some(Reply {
+                outcome: Outcome::Correct,
+                cooldown: None
+            })
+        );
+        assert_eq!(
+            parse_reply(TOO_HIGH),
+            Some(Reply {
+                outcome: Outcome::TooHigh,
+                cooldown: Some(Duration::from_secs(60))
+            })
+        );
+        assert_eq!(
+            parse_reply(TOO_RECENT),
+            Some(Reply {
+                outcome: Outcome::Wait,
+                cooldown: Some(Duration::from_secs(280))
+            })
+        );
+        assert_eq!(
+            parse_reply(
+                "That's not the right answer. Because you have guessed incorrectly 4 times on \
+                 this puzzle, please wait 5 minutes before trying again."
+            ),
+            Some(Reply {
+                outcome: Outcome::Wrong,
+                cooldown: Some(Duration::from_secs(300))
+            })
+        );
+        assert_eq!(
+            parse_reply(
+                "You don't seem to be solving the right level.  Did you already complete it?"
+            )
+            .map(|reply| reply.outcome),
+            Some(Outcome::AlreadySolved)
+        );
+        assert_eq!(
+            parse_reply("<html>Puzzle inputs differ by user.</html>"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_guess_log_bounds() {
+        let mut log = GuessLog::default();
+        let too_high = Reply {
+            outcome: Outcome::TooHigh,
+            cooldown: None,
+        };
+        let too_low = Reply {
+            outcome: Outcome::TooLow,
+            cooldown: None,
+        };
+
+        log.record(3, 2, "500", &too_high, 0);
+        log.record(3, 2, "100", &too_low, 0);
+
+        assert!(log.check(3, 2, "250", 0).is_ok());
+        assert!(log.check(3, 2, "500", 0).is_err());
+        assert!(log.check(3, 2, "600", 0).is_err());
+        assert!(log.check(3, 2, "100", 0).is_err());
+        assert!(log.check(3, 2, "42", 0).is_err());
+        assert!(log.check(3, 1, "600", 0).is_ok());
+
+        log.record(
+            3,
+            2,
+            "250",
+            &Reply {
+                outcome: Outcome::Correct,
+                cooldown: None,
+            },
+            0,
+        );
+        assert!(log.check(3, 2, "251", 0).is_err());
+    }
+
+    #[test]
+    fn test_guess_log_cooldown_round_trip() {
+        let mut log = GuessLog::default();
+        log.record(1, 1, "hello world", &parse_reply(TOO_HIGH).unwrap(), 1000);
+
+        let log = GuessLog::parse(&log.to_string()).unwrap();
+
+        assert_eq!(log.next_submission(), Some(1060));
+        assert_eq!(log.guesses(1, 1), vec![("hello world", "too_high")]);
+        assert!(log.check(1, 1, "7", 1059).is_err());
+        assert!(log.check(1, 1, "7", 1060).is_ok());
+    }
+
+    #[test]
+    fn test_submit() {
+        let server = StandIn::start(vec![
+            response("200 OK", &[], TOO_HIGH),
+            response("200 OK", &[], RIGHT),
+        ]);
+        let client = client(&server);
+        let mut log = GuessLog::default();
+
+        let reply = submit(&client, &mut log, 4, 1, "1500\n", 0).unwrap();
+        assert_eq!(reply.outcome, Outcome::TooHigh);
+
+        // Ruled out by the previous reply, so nothing is sent.
+        assert!(matches!(
+            submit(&client, &mut log, 4, 1, "1600", 60),
+            Err(SubmitError::Refused(_))
+        ));
+
+        let reply = submit(&client, &mut log, 4, 1, "1400", 60).unwrap();
+        assert_eq!(reply.outcome, Outcome::Correct);
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 2);
+        assert!(requests[0].starts_with("POST /day/4/answer HTTP/1.1\r\n"));
+        assert!(requests[0].contains("Cookie: session=abc123\r\n"));
+        assert!(requests[0].contains("Content-Type: application/x-www-form-urlencoded\r\n"));
+        assert!(requests[0].ends_with("\r\n\r\nlevel=1&answer=1500"));
+        assert!(requests[1].ends_with("level=1&answer=1400"));
+    }
+
+    #[test]
+    fn test_submit_unrecognised() {
+        let server = StandIn::start(vec![response("200 OK", &[], "<html>Log in</html>")]);
+        let mut log = GuessLog::default();
+
+        assert!(matches!(
+            submit(&client(&server), &mut log, 4, 1, "1500", 0),
+            Err(SubmitError::Unrecognised(_))
+        ));
+        assert!(log.guesses(4, 1).is_empty());
+    }
+}
@@ -0,0 +1,431 @@
+//! Reader and writer for the small subset of TOML used by the files under `test_files/`.
+//!
+//! Supported: `[table]` and `["quoted table"]` headers, `key = value` pairs with string, integer
+//! and boolean values, and `#` comments.
+
+use std::fmt;
+
+use crate::PuzzleError;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Value {
+    String(String),
+    Integer(i64),
+    Boolean(bool),
+}
+
+impl Value {
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_integer(&self) -> Option<i64> {
+        match self {
+            Value::Integer(i) => Some(*i),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Value::Boolean(b) => Some(*b),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::String(s) => write!(f, "{s}"),
+            Value::Integer(i) => write!(f, "{i}"),
+            Value::Boolean(b) => write!(f, "{b}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Table {
+    pub name: String,
+    pub entries: Vec<(String, Value)>,
+}
+
+impl Table {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            entries: vec![],
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.entries
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, value)| value)
+    }
+
+    /// Sets `key`, replacing an existing value in place so the file keeps its order.
+    pub fn set(&mut self, key: &str, value: Value) {
+        match self.entries.iter_mut().find(|(k, _)| k == key) {
+            Some(entry) => entry.1 = value,
+            None => self.entries.push((key.to_string(), value)),
+        }
+    }
+}
+
+/// A parsed file. Keys that appear before any header live in the table named `""`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Document {
+    pub tables: Vec<Table>,
+}
+
+impl Document {
+    pub fn parse(text: &str) -> Result<Self, PuzzleError> {
+        let mut tables = vec![Table::new("")];
+
+        for (i, raw_line) in text.lines().enumerate() {
+            let line = i + 1;
+            let content = strip_comment(raw_line).trim();
+
+            if content.is_empty() {
+                continue;
+            }
+
+            if let Some(header) = content.strip_prefix('[') {
+                let Some(name) = header.strip_suffix(']') else {
+                    return Err(PuzzleError::at_token(
+                        line,
+                        raw_line,
+                        content,
+                        "unterminated table header",
+                    ));
+                };
+
+                let name = parse_key(name.trim(), line, raw_line)?;
+                if tables.iter().any(|table| table.name == name) {
+                    return Err(PuzzleError::at_token(
+                        line,
+                        raw_line,
+                        content,
+                        format!("duplicate table '{name}'"),
+                    ));
+                }
+
+                tables.push(Table::new(name));
+                continue;
+            }
+
+            let Some((key, value)) = split_key(content) else {
+                return Err(PuzzleError::at_token(
+                    line,
+                    raw_line,
+                    content,
+                    "expected 'key = value'",
+                ));
+            };
+
+            let key = parse_key(key.trim(), line, raw_line)?;
+            let value = parse_value(value.trim(), line, raw_line)?;
+
+            let table = tables.last_mut().unwrap();
+            if table.get(&key).is_some() {
+                return Err(PuzzleError::at_token(
+                    line,
+                    raw_line,
+                    content,
+                    format!("duplicate key '{key}'"),
+                ));
+            }
+
+            table.entries.push((key, value));
+        }
+
+        Ok(Self { tables })
+    }
+
+    pub fn table(&self, name: &str) -> Option<&Table> {
+        self.tables.iter().find(|table| table.name == name)
+    }
+
+    /// Returns the named table, adding an empty one if it doesn't exist yet. The root table (named
+    /// `""`) goes first, since its keys would otherwise be read back as part of the table before.
+    pub fn table_mut(&mut self, name: &str) -> &mut Table {
+        let index = match self.tables.iter().position(|table| table.name == name) {
+            Some(index) => index,
+            None if name.is_empty() => {
+                self.tables.insert(0, Table::new(name));
+                0
+            }
+            None => {
+                self.tables.push(Table::new(name));
+                self.tables.len() - 1
+            }
+        };
+
+        &mut self.tables[index]
+    }
+}
+
+impl fmt::Display for Document {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut first = true;
+
+        for table in &self.tables {
+            if table.name.is_empty() && table.entries.is_empty() {
+                continue;
+            }
+
+            if !first {
+                writeln!(f)?;
+            }
+            first = false;
+
+            if !table.name.is_empty() {
+                writeln!(f, "[{}]", format_key(&table.name))?;
+            }
+
+            for (key, value) in &table.entries {
+                writeln!(f, "{} = {}", format_key(key), format_value(value))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn strip_comment(line: &str) -> &str {
+    let mut in_string = false;
+    let mut escaped = false;
+
+    for (i, c) in line.char_indices() {
+        if escaped {
+            escaped = false;
+            continue;
+        }
+
+        match c {
+            '\\' if in_string => escaped = true,
+            '"' => in_string = !in_string,
+            '#' if !in_string => return &line[..i],
+            _ => {}
+        }
+    }
+
+    line
+}
+
+/// Splits `key = value` at the first `=` after the key, so a quoted key may contain `=` itself.
+fn split_key(content: &str) -> Option<(&str, &str)> {
+    let key_end = match content.strip_prefix('"') {
+        Some(rest) => string_end(rest)? + 2,
+        None => content.find('=')?,
+    };
+
+    let (key, rest) = content.split_at(key_end);
+    let value = rest.trim_start().strip_prefix('=')?;
+
+    Some((key, value))
+}
+
+/// The byte offset of the quote that closes a string, given the text after its opening quote.
+fn string_end(text: &str) -> Option<usize> {
+    let mut escaped = false;
+
+    for (i, c) in text.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '"' => return Some(i),
+            _ => {}
+        }
+    }
+
+    None
+}
+
+fn is_bare_key(key: &str) -> bool {
+    !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+fn parse_key(text: &str, line: usize, raw_line: &str) -> Result<String, PuzzleError> {
+    if text.starts_with('"') {
+        return parse_string(text, line, raw_line);
+    }
+
+    if !is_bare_key(text) {
+        return Err(PuzzleError::at_token(
+            line,
+            raw_line,
+            text,
+            format!("invalid key '{text}', quote keys that contain '.' or spaces"),
+        ));
+    }
+
+    Ok(text.to_string())
+}
+
+fn parse_value(text: &str, line: usize, raw_line: &str) -> Result<Value, PuzzleError> {
+    match text {
+        "true" => return Ok(Value::Boolean(true)),
+        "false" => return Ok(Value::Boolean(false)),
+        _ => {}
+    }
+
+    if text.starts_with('"') {
+        return parse_string(text, line, raw_line).map(Value::String);
+    }
+
+    text.replace('_', "")
+        .parse::<i64>()
+        .map(Value::Integer)
+        .map_err(|_| PuzzleError::at_token(line, raw_line, text, format!("invalid value '{text}'")))
+}
+
+fn parse_string(text: &str, line: usize, raw_line: &str) -> Result<String, PuzzleError> {
+    let error = |message: &str| PuzzleError::at_token(line, raw_line, text, message);
+
+    let Some(inner) = text
+        .strip_prefix('"')
+        .and_then(|s| s.strip_suffix('"'))
+        .filter(|_| text.len() >= 2)
+    else {
+        return Err(error("unterminated string"));
+    };
+
+    let mut result = String::new();
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('n') => result.push('\n'),
+            Some('t') => result.push('\t'),
+            Some('"') => result.push('"'),
+            Some('\\') => result.push('\\'),
+            _ => return Err(error("invalid escape sequence")),
+        }
+    }
+
+    Ok(result)
+}
+
+fn format_key(key: &str) -> String {
+    if is_bare_key(key) {
+        key.to_string()
+    } else {
+        format_string(key)
+    }
+}
+
+fn format_value(value: &Value) -> String {
+    match value {
+        Value::String(s) => format_string(s),
+        Value::Integer(i) => i.to_string(),
+        Value::Boolean(b) => b.to_string(),
+    }
+}
+
+fn format_string(text: &str) -> String {
+    let escaped = text
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+        .replace('\t', "\\t");
+
+    format!("\"{escaped}\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let document = Document::parse(
+            "# Answers\n[\"example.txt\"]\npart_1 = 3 # inline\npart_2 = \"a # b\"\n\n[flags]\nslow = true\n",
+        )
+        .unwrap();
+
+        let table = document.table("example.txt").unwrap();
+        assert_eq!(table.get("part_1"), Some(&Value::Integer(3)));
+        assert_eq!(
+            table.get("part_2"),
+            Some(&Value::String("a # b".to_string()))
+        );
+        assert_eq!(
+            document.table("flags").unwrap().get("slow"),
+            Some(&Value::Boolean(true))
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut document = Document::default();
+        document
+            .table_mut("input.txt")
+            .set("part_1", Value::Integer(1097));
+        document
+            .table_mut("day1")
+            .set("note", Value::String("said \"hi\"\nthen left".to_string()));
+
+        let text = document.to_string();
+
+        assert_eq!(Document::parse(&text).unwrap().to_string(), text);
+        assert!(text.starts_with("[\"input.txt\"]\npart_1 = 1097\n"));
+    }
+
+    #[test]
+    fn test_quoted_keys() {
+        let mut document = Document::default();
+        document
+            .table_mut("day1_part_1")
+            .set("a=b \"c\" = d", Value::String("too high".to_string()));
+
+        let text = document.to_string();
+        let parsed = Document::parse(&text).unwrap();
+
+        assert_eq!(parsed.to_string(), text);
+        assert_eq!(
+            parsed.table("day1_part_1").unwrap().get("a=b \"c\" = d"),
+            Some(&Value::String("too high".to_string()))
+        );
+        assert_eq!(
+            Document::parse("\"a=b\" = 1").unwrap().tables[0].get("a=b"),
+            Some(&Value::Integer(1))
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            Document::parse("[a]\nkey value"),
+            Err(PuzzleError::new(2, 1, "expected 'key = value'"))
+        );
+        assert_eq!(
+            Document::parse("\"a=b 1"),
+            Err(PuzzleError::new(1, 1, "expected 'key = value'"))
+        );
+        assert_eq!(
+            Document::parse("\"a\" b = 1"),
+            Err(PuzzleError::new(1, 1, "expected 'key = value'"))
+        );
+        assert_eq!(
+            Document::parse("x = 1\nx = 2"),
+            Err(PuzzleError::new(2, 1, "duplicate key 'x'"))
+        );
+        assert_eq!(
+            Document::parse("x = nope"),
+            Err(PuzzleError::new(1, 5, "invalid value 'nope'"))
+        );
+    }
+}
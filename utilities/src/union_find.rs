@@ -0,0 +1,144 @@
+//! Disjoint sets over the indices `0..len`, for joining things into groups and asking which group
+//! something is in.
+
+#[derive(Debug, Clone)]
+pub struct UnionFind {
+    parents: Vec<usize>,
+    /// How many elements are in each set, kept up to date for the roots only.
+    sizes: Vec<usize>,
+    components: usize,
+}
+
+impl UnionFind {
+    /// `len` elements, each in a set of its own.
+    pub fn new(len: usize) -> Self {
+        Self {
+            parents: (0..len).collect(),
+            sizes: vec![1; len],
+            components: len,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.parents.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parents.is_empty()
+    }
+
+    /// The root of the set holding `element`. Every element passed on the way is pointed straight
+    /// at the root, so later lookups are quicker.
+    pub fn find(&mut self, element: usize) -> usize {
+        let mut root = element;
+        while self.parents[root] != root {
+            root = self.parents[root];
+        }
+
+        let mut current = element;
+        while self.parents[current] != root {
+            current = std::mem::replace(&mut self.parents[current], root);
+        }
+
+        root
+    }
+
+    /// Merges the sets holding `a` and `b`, hanging the smaller one off the larger. Returns false
+    /// if they were already in the same set.
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (a, b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+
+        let (larger, smaller) = if self.sizes[a] >= self.sizes[b] {
+            (a, b)
+        } else {
+            (b, a)
+        };
+
+        self.parents[smaller] = larger;
+        self.sizes[larger] += self.sizes[smaller];
+        self.components -= 1;
+
+        true
+    }
+
+    pub fn connected(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// How many elements are in the same set as `element`, itself included.
+    pub fn size(&mut self, element: usize) -> usize {
+        let root = self.find(element);
+
+        self.sizes[root]
+    }
+
+    /// How many separate sets there are.
+    pub fn components(&self) -> usize {
+        self.components
+    }
+
+    /// The size of every set, largest first.
+    pub fn component_sizes(&self) -> Vec<usize> {
+        let mut sizes = (0..self.len())
+            .filter(|element| self.parents[*element] == *element)
+            .map(|root| self.sizes[root])
+            .collect::<Vec<_>>();
+
+        sizes.sort_by(|a, b| b.cmp(a));
+
+        sizes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_union() {
+        let mut sets = UnionFind::new(6);
+
+        assert_eq!(sets.components(), 6);
+        assert!(sets.union(0, 1));
+        assert!(sets.union(2, 3));
+        assert!(sets.union(1, 3));
+        assert!(!sets.union(0, 2));
+
+        assert!(sets.connected(0, 3));
+        assert!(!sets.connected(0, 4));
+        assert_eq!(sets.size(2), 4);
+        assert_eq!(sets.size(5), 1);
+        assert_eq!(sets.components(), 3);
+        assert_eq!(sets.component_sizes(), vec![4, 1, 1]);
+    }
+
+    #[test]
+    fn test_find_compresses_paths() {
+        let mut sets = UnionFind::new(4);
+
+        // Build a chain by hand so there's a long path to compress.
+        sets.parents = vec![0, 0, 1, 2];
+        sets.sizes = vec![4, 1, 1, 1];
+
+        assert_eq!(sets.find(3), 0);
+        assert_eq!(sets.parents, vec![0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_union_by_size() {
+        let mut sets = UnionFind::new(5);
+
+        sets.union(0, 1);
+        sets.union(0, 2);
+        sets.union(3, 0);
+
+        // The single element joins the larger set rather than the other way round.
+        let root = sets.find(0);
+        assert_eq!(sets.parents[3], root);
+        assert!(sets.union(4, 3));
+        assert_eq!(sets.component_sizes(), vec![5]);
+    }
+}